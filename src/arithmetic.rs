@@ -0,0 +1,163 @@
+use cbor_data::{Cbor, CborBuilder, CborOwned, ItemKind, Literal, Writer};
+
+/// Binary operator of an arithmetic expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ArithmeticOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// Unary numeric function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NumericFunction {
+    Abs,
+    Floor,
+    Ceil,
+    Round,
+}
+
+/// Operand of an arithmetic expression:
+/// a `CBOR` integer, positive or negative, or a float
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Int(i128),
+    Float(f64),
+}
+
+impl Operand {
+    /// Returns [`None`] if `value` is neither an integer nor a float
+    fn from_cbor(value: &Cbor) -> Option<Self> {
+        match value.kind() {
+            ItemKind::Pos(v) => Some(Operand::Int(v.into())),
+            ItemKind::Neg(v) => Some(Operand::Int(-1 - i128::from(v))),
+            ItemKind::Float(v) => Some(Operand::Float(v)),
+            _ => None,
+        }
+    }
+
+    /// Returns [`None`] if the result cannot be represented as a `CBOR` number:
+    /// an integer out of [-2^64, 2^64[, an infinite float or NaN
+    fn into_cbor(self) -> Option<CborOwned> {
+        match self {
+            Operand::Int(i) if i >= 0 => {
+                Some(CborBuilder::new().write_pos(u64::try_from(i).ok()?, None))
+            }
+            Operand::Int(i) => {
+                Some(CborBuilder::new().write_neg(u64::try_from(-1 - i).ok()?, None))
+            }
+            Operand::Float(f) if f.is_finite() => {
+                Some(CborBuilder::new().write_lit(Literal::L8(f.to_bits()), None))
+            }
+            Operand::Float(_) => None,
+        }
+    }
+
+    fn as_float(self) -> f64 {
+        match self {
+            Operand::Int(i) => i as f64,
+            Operand::Float(f) => f,
+        }
+    }
+}
+
+impl ArithmeticOperator {
+    /// Computes `left <operator> right`
+    ///
+    /// The result is an integer if both operands are integers, except for a division
+    /// which is not exact, and a float otherwise.
+    /// It is [`None`] (`Nothing`) if an operand is not a number, on a division by zero
+    /// or if the result cannot be represented as a `CBOR` number.
+    pub fn apply(self, left: &Cbor, right: &Cbor) -> Option<CborOwned> {
+        let left = Operand::from_cbor(left)?;
+        let right = Operand::from_cbor(right)?;
+
+        let result = match (left, right) {
+            (Operand::Int(l), Operand::Int(r)) => match self {
+                ArithmeticOperator::Add => Operand::Int(l.checked_add(r)?),
+                ArithmeticOperator::Sub => Operand::Int(l.checked_sub(r)?),
+                ArithmeticOperator::Mul => Operand::Int(l.checked_mul(r)?),
+                ArithmeticOperator::Div if r != 0 && l % r == 0 => Operand::Int(l / r),
+                ArithmeticOperator::Div if r != 0 => Operand::Float(l as f64 / r as f64),
+                ArithmeticOperator::Div => return None,
+                ArithmeticOperator::Rem => Operand::Int(l.checked_rem(r)?),
+            },
+            _ => {
+                let (l, r) = (left.as_float(), right.as_float());
+                match self {
+                    ArithmeticOperator::Add => Operand::Float(l + r),
+                    ArithmeticOperator::Sub => Operand::Float(l - r),
+                    ArithmeticOperator::Mul => Operand::Float(l * r),
+                    ArithmeticOperator::Div | ArithmeticOperator::Rem if r == 0.0 => return None,
+                    ArithmeticOperator::Div => Operand::Float(l / r),
+                    ArithmeticOperator::Rem => Operand::Float(l % r),
+                }
+            }
+        };
+
+        result.into_cbor()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ArithmeticOperator::Add => "+",
+            ArithmeticOperator::Sub => "-",
+            ArithmeticOperator::Mul => "*",
+            ArithmeticOperator::Div => "/",
+            ArithmeticOperator::Rem => "%",
+        }
+    }
+
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "+" => Some(ArithmeticOperator::Add),
+            "-" => Some(ArithmeticOperator::Sub),
+            "*" => Some(ArithmeticOperator::Mul),
+            "/" => Some(ArithmeticOperator::Div),
+            "%" => Some(ArithmeticOperator::Rem),
+            _ => None,
+        }
+    }
+}
+
+impl NumericFunction {
+    /// Applies the function to `value`
+    ///
+    /// Integers are left unchanged by `floor`, `ceil` and `round`,
+    /// `round` rounds half-way floats away from zero.
+    /// The result is [`None`] (`Nothing`) if `value` is not a number
+    /// or if the result cannot be represented as a `CBOR` number.
+    pub fn apply(self, value: &Cbor) -> Option<CborOwned> {
+        let result = match (self, Operand::from_cbor(value)?) {
+            (NumericFunction::Abs, Operand::Int(i)) => Operand::Int(i.abs()),
+            (NumericFunction::Abs, Operand::Float(f)) => Operand::Float(f.abs()),
+            (_, Operand::Int(i)) => Operand::Int(i),
+            (NumericFunction::Floor, Operand::Float(f)) => Operand::Float(f.floor()),
+            (NumericFunction::Ceil, Operand::Float(f)) => Operand::Float(f.ceil()),
+            (NumericFunction::Round, Operand::Float(f)) => Operand::Float(f.round()),
+        };
+
+        result.into_cbor()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NumericFunction::Abs => "abs",
+            NumericFunction::Floor => "floor",
+            NumericFunction::Ceil => "ceil",
+            NumericFunction::Round => "round",
+        }
+    }
+
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "abs" => Some(NumericFunction::Abs),
+            "floor" => Some(NumericFunction::Floor),
+            "ceil" => Some(NumericFunction::Ceil),
+            "round" => Some(NumericFunction::Round),
+            _ => None,
+        }
+    }
+}
@@ -2,12 +2,25 @@
 Fluent API to build a [`CborPath`](CborPath) instance
 */
 use crate::{
+    arithmetic::{ArithmeticOperator, NumericFunction},
+    iregexp::IRegexp,
+    strings::{CaseConversion, StringTest},
+    type_check::TypeCheck,
     AbsolutePath, BooleanExpr, CborPath, Comparable, ComparisonExpr, ComparisonOperator, Error,
-    FilterSelector, Function, IndexSelector, KeySelector, FilterPath, RelativePath, Segment, Selector,
-    SingularPath, SingularSegment, SliceSelector,
+    FilterPath, FilterSelector, Function, IndexSelector, KeySelector, MembershipExpr,
+    MembershipOperator, RegexArgument, RelativePath, Segment, Selector, SingularPath,
+    SingularSegment, SliceSelector, TagSelector,
+};
+use cbor_data::{
+    constants::{TAG_BIGNUM_POS, TAG_EPOCH, TAG_ISO8601},
+    value::{Exponential, Number},
+    Cbor, CborBuilder, CborOwned, CborValue, Encoder, Literal, Writer,
+};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    ops::{BitAnd, BitOr, Not},
 };
-use cbor_data::{CborOwned, CborBuilder, Writer, Literal, Cbor};
-use regex::Regex;
 
 /// Represents an absolute path (beginning by a '$')
 ///
@@ -16,6 +29,7 @@ use regex::Regex;
 /// * the [`count`](count) function
 /// * the [`value`](value) function
 /// * the [`filter`](SegmentBuilder::filter) selector.
+///
 /// In this case the path is used in an existence test within the filter
 #[inline]
 pub fn abs_path() -> PathBuilder {
@@ -29,6 +43,7 @@ pub fn abs_path() -> PathBuilder {
 /// * the [`count`](count) function
 /// * the [`value`](value) function
 /// * the [`filter`](SegmentBuilder::filter) selector.
+///
 /// In this case the path is used in an existence test within the filter
 #[inline]
 pub fn rel_path() -> PathBuilder {
@@ -47,6 +62,8 @@ pub fn segment() -> SegmentBuilder {
 /// Represents a `logical AND` between two [`boolean expressions`](BooleanExprBuilder)
 ///
 /// Note that a [`path`](PathBuilder) can be used as one of the two [`boolean expressions`](BooleanExprBuilder)
+///
+/// The `&` operator can also be applied to a [`boolean expression`](BooleanExprBuilder): `left & right`
 #[inline]
 pub fn and<B1, B2>(left: B1, right: B2) -> BooleanExprBuilder
 where
@@ -62,6 +79,8 @@ where
 /// Represents a `logical OR` between two [`boolean expressions`](BooleanExprBuilder)
 ///
 /// Note that a [`path`](PathBuilder) can be used as one of the two [`boolean expressions`](BooleanExprBuilder)
+///
+/// The `|` operator can also be applied to a [`boolean expression`](BooleanExprBuilder): `left | right`
 #[inline]
 pub fn or<B1, B2>(left: B1, right: B2) -> BooleanExprBuilder
 where
@@ -77,6 +96,8 @@ where
 /// Represents a `logical NOT` on a [`boolean expression`](BooleanExprBuilder)
 ///
 /// Note that a [`path`](PathBuilder) can be used as the [`boolean expression`](BooleanExprBuilder)
+///
+/// The `!` operator can also be applied to a [`boolean expression`](BooleanExprBuilder): `!expr`
 #[inline]
 pub fn not<B>(expr: B) -> BooleanExprBuilder
 where
@@ -181,39 +202,244 @@ where
     )))
 }
 
+/// Represents a test that a value is an element of an array within a [`filter`](SegmentBuilder::filter).
+///
+/// Elements are compared with `value` as in an [`eq`] comparison.
+/// The test is `false` if `array` is not an array.
+///
+/// # Arguments
+/// * `value` - [`comparable`](ComparableBuilder) to look for
+/// * `array` - literal array (e.g. built with [`val`]) or [`singular path`](SingularPathBuilder) to an array
+#[inline]
+pub fn _in<C1, C2>(value: C1, array: C2) -> BooleanExprBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    BooleanExprBuilder::new(BooleanExpr::Membership(MembershipExpr::new(
+        value.into().build(),
+        MembershipOperator::In,
+        array.into().build(),
+    )))
+}
+
+/// Represents a test that a value is not an element of an array within a [`filter`](SegmentBuilder::filter).
+///
+/// This is the negation of [`_in`]: the test is `true` if `array` is not an array.
+#[inline]
+pub fn nin<C1, C2>(value: C1, array: C2) -> BooleanExprBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    BooleanExprBuilder::new(BooleanExpr::Membership(MembershipExpr::new(
+        value.into().build(),
+        MembershipOperator::Nin,
+        array.into().build(),
+    )))
+}
+
+/// Represents a test that an array has an element within a [`filter`](SegmentBuilder::filter).
+///
+/// This is [`_in`] with its operands swapped, e.g. `@.tags contains 'urgent'`.
+///
+/// # Arguments
+/// * `array` - literal array (e.g. built with [`val`]) or [`singular path`](SingularPathBuilder) to an array
+/// * `value` - [`comparable`](ComparableBuilder) to look for
+#[inline]
+pub fn array_contains<C1, C2>(array: C1, value: C2) -> BooleanExprBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    BooleanExprBuilder::new(BooleanExpr::Membership(MembershipExpr::new(
+        value.into().build(),
+        MembershipOperator::Contains,
+        array.into().build(),
+    )))
+}
+
 /// Represents a regular expression full match within a [`filter`](SegmentBuilder::filter).
 ///
 /// # Arguments
 /// * `comparable` - [`comparable`](ComparableBuilder)
-///    on which the regular expression will be applied
-/// * `regex` - Regular expression pattern in the format of the [`regex`](https://docs.rs/regex) crate.
+///   on which the regular expression will be applied
+/// * `regex` - Regular expression pattern in the [I-Regexp](https://www.rfc-editor.org/rfc/rfc9485.html) format.
 ///
-/// Implementation automatically surrounds the regular expression with `^` and `$` to force the full math
+/// The regular expression must match the whole string, e.g. `a|b` matches `"a"` and `"b"` only.
 ///
 /// Note that a [`singular path`](SingularPathBuilder) can be used as the [`comparable`](ComparableBuilder)
+///
+/// # Errors
+/// An [`Error::Conversion`] if `regex` is not a valid I-Regexp.
 #[inline]
 pub fn _match<C>(comparable: C, regex: &str) -> Result<BooleanExprBuilder, Error>
 where
     C: Into<ComparableBuilder>,
 {
-    search(comparable, &format!("^{regex}$"))
+    Ok(BooleanExprBuilder::new(BooleanExpr::Function(
+        Function::Match(
+            Box::new(comparable.into().build()),
+            RegexArgument::Literal(IRegexp::full_match(regex)?),
+        ),
+    )))
 }
 
 /// Represents a regular expression substring match within a [`filter`](SegmentBuilder::filter).
 ///
 /// # Arguments
 /// * `comparable` - [`comparable`](ComparableBuilder)
-///    on which the regular expression will be applied
-/// * `regex` - Regular expression pattern in the format of the [`regex`](https://docs.rs/regex) crate.
+///   on which the regular expression will be applied
+/// * `regex` - Regular expression pattern in the [I-Regexp](https://www.rfc-editor.org/rfc/rfc9485.html) format.
 ///
 /// Note that a [`singular path`](SingularPathBuilder) can be used as the [`comparable`](ComparableBuilder)
+///
+/// # Errors
+/// An [`Error::Conversion`] if `regex` is not a valid I-Regexp.
 #[inline]
 pub fn search<C>(comparable: C, regex: &str) -> Result<BooleanExprBuilder, Error>
 where
     C: Into<ComparableBuilder>,
 {
     Ok(BooleanExprBuilder::new(BooleanExpr::Function(
-        Function::Regex(Box::new(comparable.into().build()), Regex::new(regex)?),
+        Function::Search(
+            Box::new(comparable.into().build()),
+            RegexArgument::Literal(IRegexp::substring_match(regex)?),
+        ),
+    )))
+}
+
+/// Represents a test that a string begins with another string within a [`filter`](SegmentBuilder::filter).
+///
+/// Both [`comparables`](ComparableBuilder) must be text strings or byte strings,
+/// otherwise the test is `false`.
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn starts_with<C1, C2>(value: C1, pattern: C2) -> BooleanExprBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    BooleanExprBuilder::new(BooleanExpr::Function(Function::StringTest(
+        StringTest::StartsWith,
+        Box::new(value.into().build()),
+        Box::new(pattern.into().build()),
+    )))
+}
+
+/// Represents a test that a string ends with another string within a [`filter`](SegmentBuilder::filter).
+///
+/// Both [`comparables`](ComparableBuilder) must be text strings or byte strings,
+/// otherwise the test is `false`.
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn ends_with<C1, C2>(value: C1, pattern: C2) -> BooleanExprBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    BooleanExprBuilder::new(BooleanExpr::Function(Function::StringTest(
+        StringTest::EndsWith,
+        Box::new(value.into().build()),
+        Box::new(pattern.into().build()),
+    )))
+}
+
+/// Represents a test that a string contains another string within a [`filter`](SegmentBuilder::filter).
+///
+/// Both [`comparables`](ComparableBuilder) must be text strings or byte strings,
+/// otherwise the test is `false`.
+///
+/// To test if an array has an element, use [`array_contains`].
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn contains<C1, C2>(value: C1, pattern: C2) -> BooleanExprBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    BooleanExprBuilder::new(BooleanExpr::Function(Function::StringTest(
+        StringTest::Contains,
+        Box::new(value.into().build()),
+        Box::new(pattern.into().build()),
+    )))
+}
+
+/// Represents the `lower` function, the lowercase conversion of a text string.
+///
+/// The result is `Nothing` if the argument value is not a text string.
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+#[inline]
+pub fn lower<C>(comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    ComparableBuilder::new(Comparable::Function(Function::CaseConversion(
+        CaseConversion::Lower,
+        Box::new(comparable.into().build()),
+    )))
+}
+
+/// Represents the `upper` function, the uppercase conversion of a text string.
+///
+/// The result is `Nothing` if the argument value is not a text string.
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+#[inline]
+pub fn upper<C>(comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    ComparableBuilder::new(Comparable::Function(Function::CaseConversion(
+        CaseConversion::Upper,
+        Box::new(comparable.into().build()),
+    )))
+}
+
+/// Represents the `substring` function, the part of a string from `start` included to `end` excluded.
+///
+/// Text strings are indexed by Unicode scalar values and byte strings by bytes.
+/// If `end` is [`None`], the part extends to the end of the string.
+/// Negative indices count from the end of the string and indices are clamped to the string bounds.
+///
+/// The result is `Nothing` if the argument value is not a string or if an index is not an integer.
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+#[inline]
+pub fn substring<C1, C2>(
+    comparable: C1,
+    start: C2,
+    end: Option<ComparableBuilder>,
+) -> ComparableBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    ComparableBuilder::new(Comparable::Function(Function::Substring(
+        Box::new(comparable.into().build()),
+        Box::new(start.into().build()),
+        end.map(|end| Box::new(end.build())),
+    )))
+}
+
+/// Represents the `concat` function, the concatenation of text strings or of byte strings.
+///
+/// The result is `Nothing` if the argument values are not all text strings or all byte strings.
+/// At least one [`comparable`](ComparableBuilder) is required, which is checked when the path is built.
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+#[inline]
+pub fn concat<I, C>(comparables: I) -> ComparableBuilder
+where
+    I: IntoIterator<Item = C>,
+    C: Into<ComparableBuilder>,
+{
+    ComparableBuilder::new(Comparable::Function(Function::Concat(
+        comparables.into_iter().map(|c| c.into().build()).collect(),
     )))
 }
 
@@ -229,7 +455,11 @@ pub fn sing_rel_path() -> SingularPathBuilder {
     SingularPathBuilder::new(false)
 }
 
-/// Represents a simple `CBOR` value within a filter.
+/// Represents a `CBOR` value within a filter.
+///
+/// The value can be simple or composite: a [`Vec`] or an array is written as a `CBOR` array,
+/// a [`BTreeMap`] as a `CBOR` map, and any other value can be passed as a [`&Cbor`](Cbor).
+/// Composite values are compared element by element, e.g. `val(vec![1, 2, 3])`.
 ///
 /// Can be used wherever a [`comparable`](ComparableBuilder) is expected
 #[inline]
@@ -237,6 +467,58 @@ pub fn val<V: IntoCborOwned>(v: V) -> ComparableBuilder {
     ComparableBuilder::new(Comparable::Value(v.into()))
 }
 
+/// Represents a decimal fraction `mantissa * 10^exponent` within a filter.
+///
+/// The value is written as a `CBOR` decimal fraction (tag 4),
+/// or as a simple integer if `exponent` is `0`.
+/// Decimal fractions, bignums (tags 2 and 3), integers and floats
+/// are compared numerically with each other.
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+#[inline]
+pub fn decimal(mantissa: i128, exponent: i128) -> ComparableBuilder {
+    let inverted = mantissa < 0;
+    let mantissa = if inverted { -1 - mantissa } else { mantissa };
+    let number = Number::Decimal(Exponential::new(
+        exponent,
+        Cow::Owned(mantissa.to_be_bytes().to_vec()),
+        inverted,
+    ));
+    ComparableBuilder::new(Comparable::Value(CborBuilder::new().encode_number(&number)))
+}
+
+/// Represents a date/time value within a filter, as a `CBOR` standard date/time string (tag 0).
+///
+/// Date/time values (tags 0 and 1) are compared by the instant they represent,
+/// e.g. `0("1970-01-01T00:01:00Z")` and `1(60)` are equal.
+///
+/// # Errors
+/// [`Error::Conversion`] if `rfc3339` is not a valid [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339) date/time
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+pub fn datetime(rfc3339: &str) -> Result<ComparableBuilder, Error> {
+    let value = CborBuilder::new().write_str(rfc3339, [TAG_ISO8601]);
+    match value.decode() {
+        CborValue::Timestamp(_) => Ok(ComparableBuilder::new(Comparable::Value(value))),
+        _ => Err(Error::Conversion(format!("Invalid RFC 3339 date/time `{rfc3339}`"))),
+    }
+}
+
+/// Represents a date/time value within a filter, as a `CBOR` epoch-based date/time (tag 1).
+///
+/// `seconds` is the number of seconds since 1970-01-01T00:00Z in UTC time.
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+#[inline]
+pub fn epoch(seconds: i64) -> ComparableBuilder {
+    let value = if seconds >= 0 {
+        CborBuilder::new().write_pos(seconds as u64, [TAG_EPOCH])
+    } else {
+        CborBuilder::new().write_neg((-1 - seconds) as u64, [TAG_EPOCH])
+    };
+    ComparableBuilder::new(Comparable::Value(value))
+}
+
 /// Represents the `length` function within a [`filter`](SegmentBuilder::filter)
 ///
 /// The `length` function extension provides a way to compute the length of a value
@@ -249,11 +531,12 @@ pub fn val<V: IntoCborOwned>(v: V) -> ComparableBuilder {
 /// (possibly taken from a [`singular path`](SingularPathBuilder) as in the example above).
 ///
 /// The result also is a [`comparable`](ComparableBuilder), an unsigned integer.
-/// * If the argument value is a `text string`, the result is the number of UTF8 characters in the string.
-/// * If the argument value is a `byte string`, the result is the size of the byte buffer.
+/// * If the argument value is a `text string`, the result is the number of Unicode scalar values in the string
+///   (not the number of bytes of its UTF-8 encoding).
+/// * If the argument value is a `byte string`, the result is the number of bytes in the string.
 /// * If the argument value is an `array`, the result is the number of elements in the array.
 /// * If the argument value is a `map`, the result is the number of items in the map.
-/// * For any other argument value, the result is `1`.
+/// * For any other argument value, the result is `Nothing`.
 ///
 /// Can be used wherever a [`comparable`](ComparableBuilder) is expected
 #[inline]
@@ -266,6 +549,168 @@ where
     ))))
 }
 
+/// Represents the `tag` function within a [`filter`](SegmentBuilder::filter)
+///
+/// The `tag` function extension provides a way to get the tag number of a value
+/// and make that available for further processing in the filter expression:
+/// ```json
+/// ["$", {"?": {"==": [{"tag": ["@", "uri"]}, 32]}}]
+/// ```
+///
+/// Its only argument is a [`comparable`](ComparableBuilder).
+///
+/// The result is a [`comparable`](ComparableBuilder), the unsigned integer of the outermost tag
+/// of the argument value, or `None` if the argument value is not tagged.
+///
+/// Can be used wherever a [`comparable`](ComparableBuilder) is expected
+#[inline]
+pub fn tag<C>(comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    ComparableBuilder::new(Comparable::Function(Function::Tag(Box::new(
+        comparable.into().comparable,
+    ))))
+}
+
+/// Represents an addition `left + right` between two [`comparables`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn add<C1, C2>(left: C1, right: C2) -> ComparableBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    arithmetic(ArithmeticOperator::Add, left, right)
+}
+
+/// Represents a subtraction `left - right` between two [`comparables`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn sub<C1, C2>(left: C1, right: C2) -> ComparableBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    arithmetic(ArithmeticOperator::Sub, left, right)
+}
+
+/// Represents a multiplication `left * right` between two [`comparables`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn mul<C1, C2>(left: C1, right: C2) -> ComparableBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    arithmetic(ArithmeticOperator::Mul, left, right)
+}
+
+/// Represents a division `left / right` between two [`comparables`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn div<C1, C2>(left: C1, right: C2) -> ComparableBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    arithmetic(ArithmeticOperator::Div, left, right)
+}
+
+/// Represents the remainder of a division `left % right` between two [`comparables`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+///
+/// Note that a [`singular path`](SingularPathBuilder) can be used as one of the two [`comparables`](ComparableBuilder)
+#[inline]
+pub fn rem<C1, C2>(left: C1, right: C2) -> ComparableBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    arithmetic(ArithmeticOperator::Rem, left, right)
+}
+
+fn arithmetic<C1, C2>(operator: ArithmeticOperator, left: C1, right: C2) -> ComparableBuilder
+where
+    C1: Into<ComparableBuilder>,
+    C2: Into<ComparableBuilder>,
+{
+    ComparableBuilder::new(Comparable::Function(Function::Arithmetic(
+        operator,
+        Box::new(left.into().comparable),
+        Box::new(right.into().comparable),
+    )))
+}
+
+/// Represents the `abs` function, the absolute value a [`comparable`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+#[inline]
+pub fn abs<C>(comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    numeric(NumericFunction::Abs, comparable)
+}
+
+/// Represents the `floor` function, the largest integer lesser than or equal to a [`comparable`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+#[inline]
+pub fn floor<C>(comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    numeric(NumericFunction::Floor, comparable)
+}
+
+/// Represents the `ceil` function, the smallest integer greater than or equal to a [`comparable`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+#[inline]
+pub fn ceil<C>(comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    numeric(NumericFunction::Ceil, comparable)
+}
+
+/// Represents the `round` function, the nearest integer to a [`comparable`](ComparableBuilder)
+///
+/// See [`arithmetic expressions`](ComparableBuilder#arithmetic-expressions) for the result.
+///
+/// Half-way floats are rounded away from zero, e.g. `round(-2.5)` is `-3.0`.
+#[inline]
+pub fn round<C>(comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    numeric(NumericFunction::Round, comparable)
+}
+
+fn numeric<C>(function: NumericFunction, comparable: C) -> ComparableBuilder
+where
+    C: Into<ComparableBuilder>,
+{
+    ComparableBuilder::new(Comparable::Function(Function::Numeric(
+        function,
+        Box::new(comparable.into().comparable),
+    )))
+}
+
 /// Represents the `count` function within a [`filter`](SegmentBuilder::filter)
 ///
 /// The `count` function extension provides a way to obtain the number of nodes in a [`path`](PathBuilder)
@@ -309,6 +754,7 @@ pub fn value(path: PathBuilder) -> ComparableBuilder {
 /// A `path` always begins by an identifier
 /// * `$` for absolute paths,
 /// * `@` for relative paths.
+///
 /// This identifier is automatically included by the [`abs_path`] and the [`rel_path`] function.
 ///
 /// A `path` is then followed by one or more [`segments`](SegmentBuilder)
@@ -381,7 +827,14 @@ impl PathBuilder {
 
     /// Shortcut for a [`child`](PathBuilder::child) segment with a unique [`slice`](SegmentBuilder::slice) selector.
     #[inline]
-    pub fn slice(mut self, start: isize, end: isize, step: isize) -> Self {
+    pub fn slice(self, start: isize, end: isize, step: isize) -> Self {
+        self.slice_bounds(Some(start), Some(end), step)
+    }
+
+    /// Shortcut for a [`child`](PathBuilder::child) segment with a unique [`slice_bounds`](SegmentBuilder::slice_bounds) selector
+    /// with optional bounds.
+    #[inline]
+    pub fn slice_bounds(mut self, start: Option<isize>, end: Option<isize>, step: isize) -> Self {
         self.segments
             .push(Segment::Child(vec![Selector::Slice(SliceSelector::new(
                 start, end, step,
@@ -399,12 +852,40 @@ impl PathBuilder {
         self
     }
 
+    /// Shortcut for a [`child`](PathBuilder::child) segment with a unique [`tag`](SegmentBuilder::tag) selector.
+    #[inline]
+    pub fn tag(mut self, tag: u64) -> Self {
+        self.segments
+            .push(Segment::Child(vec![Selector::Tag(TagSelector::new(tag))]));
+        self
+    }
+
+    /// Shortcut for a [`child`](PathBuilder::child) segment with a unique [`embedded`](SegmentBuilder::embedded) selector.
+    #[inline]
+    pub fn embedded(mut self) -> Self {
+        self.segments.push(Segment::Child(vec![Selector::Embedded]));
+        self
+    }
+
     /// Build a [`CborPath`] from the builder
+    ///
+    /// Filter expressions are not type checked, see [`try_build`](PathBuilder::try_build)
     #[inline]
     pub fn build(self) -> CborPath {
         CborPath::new(self.segments)
     }
 
+    /// Build a [`CborPath`] from the builder, checking that filter expressions are well-typed
+    ///
+    /// # Errors
+    /// An [`Error::Conversion`] if a filter expression is not well-typed,
+    /// e.g. a function returning a logical value used as a comparable.
+    #[inline]
+    pub fn try_build(self) -> Result<CborPath, Error> {
+        self.segments.type_check()?;
+        Ok(CborPath::new(self.segments))
+    }
+
     #[inline]
     pub(crate) fn build_path(self) -> FilterPath {
         if self.is_absolute {
@@ -433,6 +914,9 @@ impl SegmentBuilder {
     /// Applying the `key selector` to a `CBOR map` node selects an item value whose `key`
     /// equals the item key from the selector, or selects nothing if there is no such item value.
     /// Nothing is selected from a value that is not a `CBOR map`.
+    ///
+    /// The key can be any `CBOR` value, including an array or a map (e.g. a [`Vec`] or a [`&Cbor`](Cbor)),
+    /// which is written `{"key": <key>}` in the `CBOR` form of the path.
     #[inline]
     pub fn key<V: IntoCborOwned>(mut self, key: V) -> Self {
         self.selectors
@@ -478,7 +962,18 @@ impl SegmentBuilder {
     /// # See
     /// <https://www.ietf.org/archive/id/draft-ietf-jsonpath-base-09.html#name-array-slice-selector>
     #[inline]
-    pub fn slice(mut self, start: isize, end: isize, step: isize) -> Self {
+    pub fn slice(self, start: isize, end: isize, step: isize) -> Self {
+        self.slice_bounds(Some(start), Some(end), step)
+    }
+
+    /// Adds a `slice selector` with optional bounds to the segment.
+    ///
+    /// `start` and `end` can be [`None`] to use their default value, depending on the sign of `step`,
+    /// e.g. `slice_bounds(None, None, -1)` selects the elements of an array in reverse order (`[::-1]`).
+    ///
+    /// See [`slice`](SegmentBuilder::slice)
+    #[inline]
+    pub fn slice_bounds(mut self, start: Option<isize>, end: Option<isize>, step: isize) -> Self {
         self.selectors
             .push(Selector::Slice(SliceSelector::new(start, end, step)));
         self
@@ -499,13 +994,13 @@ impl SegmentBuilder {
     ///
     /// When a path resulting in an empty nodelist appears on either side of a comparison:
     /// * a comparison using the operator `==` yields true if and only if the comparison
-    /// is between two paths each of which result in an empty nodelist.
+    ///   is between two paths each of which result in an empty nodelist.
     /// * a comparison using the operator `<` yields false.
     ///
     /// When any path on either side of a comparison results in a nodelist consisting of a single node, each such path is
     /// replaced by the value of its node and then:
     /// * a comparison using the operator `==` yields true if and only if the comparison
-    /// is between:
+    ///   is between:
     ///     * equal primitive values,
     ///     * equal arrays, that is arrays of the same length where each element of the first array is equal to the corresponding
     ///       element of the second array, or
@@ -513,7 +1008,7 @@ impl SegmentBuilder {
     ///         * both maps have the same collection of keys (with no duplicates), and
     ///         * for each of those keys, the values associated with the key by the maps are equal.
     /// * a comparison using the operator `<` yields true if and only if
-    /// the comparison is between values which are both numbers or both strings and which satisfy the comparison:
+    ///   the comparison is between values which are both numbers or both strings and which satisfy the comparison:
     ///     * numbers compare using the normal mathematical ordering;
     ///     * the empty string compares less than any non-empty string
     ///     * a non-empty string compares less than another non-empty string if and only if the first string starts with a
@@ -540,6 +1035,7 @@ impl SegmentBuilder {
     /// * [`length`]
     /// * [`count`]
     /// * [`value`]
+    /// * [`tag`](tag())
     #[inline]
     pub fn filter(mut self, boolean_expr: BooleanExprBuilder) -> Self {
         self.selectors
@@ -547,6 +1043,31 @@ impl SegmentBuilder {
         self
     }
 
+    /// Adds a `tag selector` to the segment.
+    ///
+    /// The `tag selector` selects the array elements or map item values whose outermost tag is `tag`.
+    /// Applied to primitive values, it will select nothing.
+    ///
+    /// It is equivalent to a [`filter`](SegmentBuilder::filter) comparing the [`tag`](tag()) function with `tag`.
+    #[inline]
+    pub fn tag(mut self, tag: u64) -> Self {
+        self.selectors.push(Selector::Tag(TagSelector::new(tag)));
+        self
+    }
+
+    /// Adds an `embedded selector` to the segment.
+    ///
+    /// The `embedded selector` selects the `CBOR` item embedded in a byte string,
+    /// untagged or with tag 24 (encoded CBOR data item).
+    /// Applied to any other value, or to a byte string which does not contain a valid `CBOR` item, it will select nothing.
+    ///
+    /// Write operations applied inside the embedded item re-encode the byte string.
+    #[inline]
+    pub fn embedded(mut self) -> Self {
+        self.selectors.push(Selector::Embedded);
+        self
+    }
+
     #[inline]
     pub(crate) fn build(self, is_child: bool) -> Segment {
         if is_child {
@@ -574,6 +1095,38 @@ impl BooleanExprBuilder {
     }
 }
 
+/// `a & b` is equivalent to [`and(a, b)`](and)
+///
+/// As in Rust, `&` has a higher precedence than `|`, like `&&` and `||` in filter expressions
+impl<B: Into<BooleanExprBuilder>> BitAnd<B> for BooleanExprBuilder {
+    type Output = BooleanExprBuilder;
+
+    #[inline]
+    fn bitand(self, rhs: B) -> Self::Output {
+        and(self, rhs)
+    }
+}
+
+/// `a | b` is equivalent to [`or(a, b)`](or)
+impl<B: Into<BooleanExprBuilder>> BitOr<B> for BooleanExprBuilder {
+    type Output = BooleanExprBuilder;
+
+    #[inline]
+    fn bitor(self, rhs: B) -> Self::Output {
+        or(self, rhs)
+    }
+}
+
+/// `!a` is equivalent to [`not(a)`](not)
+impl Not for BooleanExprBuilder {
+    type Output = BooleanExprBuilder;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        not(self)
+    }
+}
+
 impl From<PathBuilder> for BooleanExprBuilder {
     #[inline]
     fn from(path: PathBuilder) -> Self {
@@ -589,6 +1142,19 @@ impl From<PathBuilder> for BooleanExprBuilder {
 /// * A [`value`](val)
 /// * A [`singular path`](SingularPathBuilder)
 /// * A `function` ([`match`](_match), [`search`], [`length`], [`count`] or [`value`])
+/// * An arithmetic expression ([`add`], [`sub`], [`mul`], [`div`], [`rem`], [`abs`], [`floor`], [`ceil`] or [`round`])
+/// * A string function ([`lower`], [`upper`], [`substring`] or [`concat`])
+///
+/// # Arithmetic expressions
+/// Arithmetic expressions are defined over integers and floats (tags are ignored):
+/// * The result of an operation between two integers is an integer,
+///   except for a division which is not exact, whose result is a float.
+/// * The result of an operation involving a float is a float.
+/// * `floor`, `ceil` and `round` leave integers unchanged.
+///
+/// The result is `Nothing` if an operand is not a number, on a division by zero,
+/// or if the result cannot be represented as a `CBOR` number
+/// (an integer out of the range of `CBOR` integers, an infinite float or NaN).
 pub struct ComparableBuilder {
     comparable: Comparable,
 }
@@ -691,6 +1257,23 @@ impl IntoCborOwned for i64 {
     }
 }
 
+/// Written as a bignum (tag 2 or 3) if the value does not fit in a simple `CBOR` integer
+impl IntoCborOwned for i128 {
+    fn into(self) -> CborOwned {
+        CborBuilder::new().encode_number(&Number::Int(self))
+    }
+}
+
+/// Written as a bignum (tag 2) if the value does not fit in a simple `CBOR` integer
+impl IntoCborOwned for u128 {
+    fn into(self) -> CborOwned {
+        match i128::try_from(self) {
+            Ok(i) => IntoCborOwned::into(i),
+            Err(_) => CborBuilder::new().write_bytes(&self.to_be_bytes(), [TAG_BIGNUM_POS]),
+        }
+    }
+}
+
 impl IntoCborOwned for u32 {
     fn into(self) -> CborOwned {
         CborBuilder::new().write_pos(self as u64, None)
@@ -775,8 +1358,40 @@ impl IntoCborOwned for () {
     }
 }
 
-impl<'a> IntoCborOwned for &'a Cbor {
+impl IntoCborOwned for &Cbor {
     fn into(self) -> CborOwned {
         self.to_owned()
     }
 }
+
+/// Written as a `CBOR` array (a `&[u8]` slice is written as a byte string)
+impl<V: IntoCborOwned> IntoCborOwned for Vec<V> {
+    fn into(self) -> CborOwned {
+        CborBuilder::new().write_array(None, |builder| {
+            for value in self {
+                builder.write_item(&IntoCborOwned::into(value));
+            }
+        })
+    }
+}
+
+/// Written as a `CBOR` array
+impl<V: IntoCborOwned, const N: usize> IntoCborOwned for [V; N] {
+    fn into(self) -> CborOwned {
+        IntoCborOwned::into(Vec::from(self))
+    }
+}
+
+/// Written as a `CBOR` map, in the order of the keys
+impl<K: IntoCborOwned, V: IntoCborOwned> IntoCborOwned for BTreeMap<K, V> {
+    fn into(self) -> CborOwned {
+        CborBuilder::new().write_dict(None, |builder| {
+            for (key, value) in self {
+                builder.with_cbor_key(
+                    |b| b.write_item(&IntoCborOwned::into(key)),
+                    |b| b.write_item(&IntoCborOwned::into(value)),
+                );
+            }
+        })
+    }
+}
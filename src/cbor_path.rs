@@ -1,26 +1,44 @@
 use crate::{
+    arithmetic::{ArithmeticOperator, NumericFunction},
     builder::{self, IntoCborOwned, PathBuilder},
+    conversion::ToCbor,
+    display,
+    function_registry::{FunctionArgument, FunctionDefinition, FunctionRegistry},
+    iregexp::IRegexp,
+    number::Number,
+    parser,
+    strings::{self, CaseConversion, StringTest},
     write_visitor::WriteVisitor,
     Error,
 };
-use cbor_data::{Cbor, CborBuilder, CborOwned, ItemKind, Writer};
-use regex::Regex;
+use cbor_data::{
+    constants::{TAG_CBOR_ITEM, TAG_EPOCH, TAG_ISO8601},
+    Cbor, CborBuilder, CborOwned, CborValue, ItemKind, Writer,
+};
 use std::{
     borrow::Cow,
     fmt::{self, Display, Formatter},
+    iter, mem,
     ops::Deref,
+    str::FromStr,
+    sync::Arc,
     vec,
 };
 
 /// A path element
 ///
+/// New kinds of elements may be added, matches on this enum need a wildcard arm.
+///
 /// See [`CborPath::get_paths`](CborPath::get_paths)
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum PathElement {
     /// Index in a `CBOR Array`
     Index(usize),
     /// Key in a `CBOR Map`
     Key(CborOwned),
+    /// `CBOR` item embedded in a byte string
+    Embedded,
 }
 
 impl Display for PathElement {
@@ -28,6 +46,7 @@ impl Display for PathElement {
         match self {
             PathElement::Index(index) => write!(f, "{index}"),
             PathElement::Key(key) => write!(f, "{key}"),
+            PathElement::Embedded => f.write_str("<<>>"),
         }
     }
 }
@@ -53,6 +72,12 @@ impl Path {
         Self(self.0)
     }
 
+    /// Builder function to add to the `Path` the `CBOR` item embedded in a byte string.
+    pub fn embedded(mut self) -> Self {
+        self.0.push(PathElement::Embedded);
+        Self(self.0)
+    }
+
     /// Creates a new Path that is child of the current path,
     /// by adding an index in an array.
     pub fn child_from_idx(&self, index: usize) -> Self {
@@ -71,6 +96,15 @@ impl Path {
         Self(path)
     }
 
+    /// Creates a new Path that is child of the current path,
+    /// by adding the `CBOR` item embedded in a byte string.
+    pub fn child_from_embedded(&self) -> Self {
+        let mut path = Vec::with_capacity(self.0.len() + 1);
+        path.extend(self.0.iter().cloned());
+        path.push(PathElement::Embedded);
+        Self(path)
+    }
+
     /// Append to the current path an index in an array
     pub fn append_idx(&mut self, index: usize) {
         self.0.push(PathElement::Index(index));
@@ -99,6 +133,184 @@ impl Path {
 
         self.0.iter().zip(other.0.iter()).all(|(r, l)| r == l)
     }
+
+    /// Gets the value this path points to in the provided `cbor` document,
+    /// without evaluating a [`CborPath`]
+    ///
+    /// # Arguments
+    /// * `cbor` - the CBOR input document
+    ///
+    /// # Return
+    /// The `CBOR` node or [`None`] if the path does not exist in the input document
+    pub fn resolve<'a>(&self, cbor: &'a Cbor) -> Option<&'a Cbor> {
+        let mut current = cbor;
+        for element in self.iter() {
+            current = match (element, current.kind()) {
+                (PathElement::Index(index), ItemKind::Array(mut array)) => array.nth(*index)?,
+                (PathElement::Key(key), ItemKind::Dict(mut dict)) => {
+                    dict.find_map(|(k, v)| if key_equals(k, key) { Some(v) } else { None })?
+                }
+                (PathElement::Embedded, _) => embedded(current)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    /// Converts the path to a singular [`CborPath`], made of child segments
+    /// with a unique key or index selector.
+    ///
+    /// The result can be used to [`read`](CborPath::read), [`set`](CborPath::set)
+    /// or [`delete`](CborPath::delete) the node this path points to.
+    pub fn to_cbor_path(&self) -> CborPath {
+        CborPath::new(
+            self.iter()
+                .map(|element| match element {
+                    PathElement::Index(index) => {
+                        Segment::Child(vec![Selector::Index(IndexSelector::new(*index as isize))])
+                    }
+                    PathElement::Key(key) => {
+                        Segment::Child(vec![Selector::Key(KeySelector::new(key.clone()))])
+                    }
+                    PathElement::Embedded => Segment::Child(vec![Selector::Embedded]),
+                })
+                .collect(),
+        )
+    }
+
+    /// Converts the path to a [normalized path](https://www.rfc-editor.org/rfc/rfc9535.html#name-normalized-paths)
+    /// (e.g. `$['store']['book'][0]`)
+    ///
+    /// Keys which are not text strings are written in
+    /// [CBOR diagnostic notation](https://www.rfc-editor.org/rfc/rfc8949.html#name-diagnostic-notation)
+    /// (e.g. `$[h'0102'][1.5]`) and embedded `CBOR` items as `[<<>>]`
+    pub fn to_normalized_path(&self) -> String {
+        struct NormalizedPath<'a>(&'a Path);
+
+        impl<'a> Display for NormalizedPath<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("$")?;
+                for element in self.0.iter() {
+                    match element {
+                        PathElement::Index(index) => write!(f, "[{index}]")?,
+                        PathElement::Key(key) => {
+                            f.write_str("[")?;
+                            display::write_literal(key, f)?;
+                            f.write_str("]")?;
+                        }
+                        PathElement::Embedded => f.write_str("[<<>>]")?,
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        NormalizedPath(self).to_string()
+    }
+
+    /// Converts the path to a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901.html)
+    /// (e.g. `/store/book/0`)
+    ///
+    /// # Return
+    /// The JSON Pointer or an error if one of the keys of the path is not a text string
+    /// or if the path goes through an embedded `CBOR` item
+    pub fn to_json_pointer(&self) -> Result<String, Error> {
+        let mut pointer = String::new();
+        for element in self.iter() {
+            pointer.push('/');
+            match element {
+                PathElement::Index(index) => pointer.push_str(&index.to_string()),
+                PathElement::Key(key) => match key.kind() {
+                    ItemKind::Str(str) if key.tags().is_empty() => {
+                        for c in str.as_cow().chars() {
+                            match c {
+                                '~' => pointer.push_str("~0"),
+                                '/' => pointer.push_str("~1"),
+                                c => pointer.push(c),
+                            }
+                        }
+                    }
+                    _ => {
+                        return Err(Error::Conversion(format!(
+                            "Key `{key}` cannot be represented in a JSON Pointer"
+                        )))
+                    }
+                },
+                PathElement::Embedded => {
+                    return Err(Error::Conversion(
+                        "Embedded CBOR cannot be represented in a JSON Pointer".to_owned(),
+                    ))
+                }
+            }
+        }
+        Ok(pointer)
+    }
+
+    /// Initialize a `Path` from a [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901.html)
+    /// (e.g. `/store/book/0`)
+    ///
+    /// Since a JSON Pointer does not make the difference between array indices and map keys,
+    /// reference tokens which are valid array indices (`0`, `1`, ...) are converted to
+    /// [`PathElement::Index`] and all other reference tokens are converted to
+    /// text string [`PathElement::Key`].
+    ///
+    /// # Return
+    /// A new `Path` or a [`Error::Syntax`] error if the provided text is not a valid JSON Pointer.
+    pub fn from_json_pointer(pointer: &str) -> Result<Self, Error> {
+        if pointer.is_empty() {
+            return Ok(Path::default());
+        }
+
+        let Some(pointer) = pointer.strip_prefix('/') else {
+            return Err(Error::Syntax("Expected `/` at column 1".to_owned()));
+        };
+
+        let mut path = Path::default();
+        let mut column = 2;
+        for token in pointer.split('/') {
+            let is_index = token == "0"
+                || (token.starts_with(|c: char| matches!(c, '1'..='9'))
+                    && token.chars().all(|c| c.is_ascii_digit()));
+
+            match token.parse::<usize>() {
+                Ok(index) if is_index => path.append_idx(index),
+                _ => {
+                    let mut key = String::with_capacity(token.len());
+                    let mut chars = token.chars();
+                    let mut offset = 0;
+                    while let Some(c) = chars.next() {
+                        if c == '~' {
+                            match chars.next() {
+                                Some('0') => key.push('~'),
+                                Some('1') => key.push('/'),
+                                _ => {
+                                    return Err(Error::Syntax(format!(
+                                        "Invalid escape sequence at column {}",
+                                        column + offset
+                                    )))
+                                }
+                            }
+                            offset += 2;
+                        } else {
+                            key.push(c);
+                            offset += 1;
+                        }
+                    }
+                    path.append_key(key.as_str());
+                }
+            }
+
+            column += token.chars().count() + 1;
+        }
+
+        Ok(path)
+    }
+}
+
+impl FromIterator<PathElement> for Path {
+    fn from_iter<T: IntoIterator<Item = PathElement>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
 }
 
 impl Deref for Path {
@@ -164,6 +376,60 @@ impl CborPath {
         cbor.try_into()
     }
 
+    /// Initialize a `CborPath` instance from a [`CBOR value`](https://docs.rs/cbor-data/latest/cbor_data/struct.Cbor.html) reference,
+    /// which can call the user-defined functions of `registry` in its filter expressions
+    ///
+    /// # Arguments
+    /// * `cbor` - the CBOR input document
+    /// * `registry` - the user-defined functions
+    ///
+    /// # Return
+    /// A new `CborPath` instance or an error if the provided [`CBOR value`] is not a valid `CBORPath` expression.
+    #[inline]
+    pub fn from_value_with(cbor: &Cbor, registry: &FunctionRegistry) -> Result<Self, Error> {
+        (cbor, registry).try_into()
+    }
+
+    /// Initialize a `CborPath` instance from a JSONPath expression in the text syntax
+    /// of [RFC 9535](https://www.rfc-editor.org/rfc/rfc9535.html)
+    ///
+    /// # Arguments
+    /// * `jsonpath` - the JSONPath expression (e.g. `$.store.book[?@.price < 10].title`)
+    ///
+    /// # Return
+    /// A new `CborPath` instance or a [`Error::Syntax`] error, with the column where
+    /// the parsing failed, if the provided text is not a valid JSONPath expression.
+    #[inline]
+    pub fn parse(jsonpath: &str) -> Result<Self, Error> {
+        parser::parse(jsonpath)
+    }
+
+    /// Converts the `CborPath` instance to its CBORPath expression,
+    /// in the form of a [`CBOR value`](https://docs.rs/cbor-data/latest/cbor_data/struct.CborOwned.html)
+    ///
+    /// # Return
+    /// A `CBOR Array` beginning by the root identifier (`$`) and followed by the path segments.
+    ///
+    /// The result can be converted back to an equal `CborPath` instance
+    /// with [`CborPath::from_value`](CborPath::from_value).
+    #[inline]
+    pub fn to_cbor(&self) -> CborOwned {
+        self.0.write_cbor(CborBuilder::new())
+    }
+
+    /// Converts the `CborPath` instance to its CBORPath expression,
+    /// in the form of a `CBOR binary buffer`
+    ///
+    /// # Return
+    /// A `CBOR Array` beginning by the root identifier (`$`) and followed by the path segments.
+    ///
+    /// The result can be converted back to an equal `CborPath` instance
+    /// with [`CborPath::from_bytes`](CborPath::from_bytes).
+    #[inline]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_cbor().into_vec()
+    }
+
     /// Initialize a `CborPath` instance as a root path
     #[inline]
     pub fn root() -> Self {
@@ -193,6 +459,40 @@ impl CborPath {
         self.0.read(cbor)
     }
 
+    /// Applies the CBORPath expression to the input `CBOR` document
+    ///
+    /// # Arguments
+    /// * `cbor` - the CBOR input document
+    ///
+    /// # Return
+    /// A lazy iterator over the matched `CBOR` nodes, in the same order as [`read`](CborPath::read).
+    ///
+    /// Nodes are only evaluated when the iterator is advanced,
+    /// which allows to stop the traversal of the input document as soon as enough nodes have been found.
+    #[inline]
+    pub fn read_iter<'p, 'a: 'p>(&'p self, cbor: &'a Cbor) -> impl Iterator<Item = &'a Cbor> + 'p {
+        self.0.read_iter(cbor)
+    }
+
+    /// Checks if the CBORPath expression matches at least one node of the input `CBOR` document
+    ///
+    /// The traversal of the input document stops as soon as a node is found.
+    #[inline]
+    pub fn exists(&self, cbor: &Cbor) -> bool {
+        self.read_iter(cbor).next().is_some()
+    }
+
+    /// Gets the first node matched by the CBORPath expression in the input `CBOR` document
+    ///
+    /// The traversal of the input document stops as soon as a node is found.
+    ///
+    /// # Return
+    /// The first matched `CBOR` node or [`None`] if the CBORPath expression does not match the input value.
+    #[inline]
+    pub fn first<'a>(&self, cbor: &'a Cbor) -> Option<&'a Cbor> {
+        self.read_iter(cbor).next()
+    }
+
     /// Applies the CBORPath expression to the input `CBOR` document
     ///
     /// # Arguments
@@ -253,13 +553,57 @@ impl CborPath {
         Ok(self.get_paths(cbor))
     }
 
+    /// Applies the CBORPath expression to the input `CBOR` document
+    ///
+    /// # Arguments
+    /// * `cbor` - the CBOR input document
+    ///
+    /// # Return
+    /// A list of matched nodes, each one with its path in the input document.
+    ///
+    /// Nodes and paths are computed in a single evaluation, in the same order as
+    /// [`read`](CborPath::read) and [`get_paths`](CborPath::get_paths).
+    ///
+    /// The evaluation in itself does not raise any error:
+    /// if the CBORPath expression does not match the input value, an empty list will be returned.
+    #[inline]
+    pub fn read_with_paths<'a>(&self, cbor: &'a Cbor) -> Vec<(Path, &'a Cbor)> {
+        self.0.read_with_paths(cbor)
+    }
+
+    /// Applies the CBORPath expression to the input `CBOR` document
+    ///
+    /// # Arguments
+    /// * `cbor` - the CBOR input document
+    ///
+    /// # Return
+    /// A list of matched nodes as binarized `CBOR` sub-documents
+    /// of the input buffer, each one with its path in the input document.
+    ///
+    /// The evaluation in itself does not raise any error:
+    /// if the CBORPath expression does not match the input value, an empty list will be returned.
+    ///
+    /// Errors can only occur if the input buffer is not a valid `CBOR` document.
+    #[inline]
+    pub fn read_with_paths_from_bytes<'a>(
+        &self,
+        cbor: &'a [u8],
+    ) -> Result<Vec<(Path, &'a [u8])>, Error> {
+        let cbor = Cbor::checked(cbor)?;
+        Ok(self
+            .read_with_paths(cbor)
+            .into_iter()
+            .map(|(path, value)| (path, value.as_ref()))
+            .collect())
+    }
+
     /// Replaces or deletes the value on the given path with the result of the `map_function`
     ///
     ///
     /// # Arguments
     /// * `cbor` - the CBOR input document
     /// * `map_function` - Returns a converted CBOR sub-document to replace
-    /// the input CBOR sub-document or [`None`] to delete the input sub-document.
+    ///   the input CBOR sub-document or [`None`] to delete the input sub-document.
     ///
     /// # Return
     /// The updated CBOR document or [`None`] if the CBORPath expression does not match the input value.
@@ -277,7 +621,8 @@ impl CborPath {
         if paths.is_empty() {
             Ok(None)
         } else {
-            let mut visitor = WriteVisitor::new(paths, map_function);
+            let mut map_function = map_function;
+            let mut visitor = WriteVisitor::new(paths, &mut map_function);
             cbor.visit(&mut visitor)?;
             Ok(Some(visitor.get_cbor()))
         }
@@ -288,7 +633,7 @@ impl CborPath {
     /// # Arguments
     /// * `cbor` - the CBOR input document
     /// * `map_function` - Returns a converted CBOR sub-document to replace
-    /// the input CBOR sub-document or [`None`] to delete the input sub-document.
+    ///   the input CBOR sub-document or [`None`] to delete the input sub-document.
     ///
     /// # Return
     /// The updated CBOR document or [`None`] if the CBORPath expression does not match the input value.
@@ -307,7 +652,7 @@ impl CborPath {
     where
         F: FnMut(&'a [u8]) -> Result<Option<Cow<'a, [u8]>>, Error>,
     {
-        let cbor_map_function = |cbor: &'a Cbor| match (map_function)(cbor.as_ref())? {
+        let mut cbor_map_function = |cbor: &'a Cbor| match (map_function)(cbor.as_ref())? {
             Some(Cow::Borrowed(bytes)) => Ok(Some(Cow::Borrowed(Cbor::checked(bytes)?))),
             Some(Cow::Owned(bytes)) => Ok(Some(Cow::Owned(CborOwned::unchecked(bytes)))),
             None => Ok(None),
@@ -319,7 +664,7 @@ impl CborPath {
         if paths.is_empty() {
             Ok(None)
         } else {
-            let mut visitor = WriteVisitor::new(paths, cbor_map_function);
+            let mut visitor = WriteVisitor::new(paths, &mut cbor_map_function);
             cbor.visit(&mut visitor)?;
             Ok(Some(visitor.get_cbor().into_vec()))
         }
@@ -402,6 +747,30 @@ impl CborPath {
     }
 }
 
+/// Displays the CBORPath expression in the JSONPath text syntax
+/// of [RFC 9535](https://www.rfc-editor.org/rfc/rfc9535.html)
+///
+/// Text keys are displayed with the member name shorthand (`.name`) when possible,
+/// in bracket notation otherwise (`['a b']`).
+/// Other keys are displayed in bracket notation with the
+/// [CBOR diagnostic notation](https://www.rfc-editor.org/rfc/rfc8949.html#name-diagnostic-notation)
+/// (e.g. `[h'0102']`, `[1.5]`) and cannot be parsed back by [`CborPath::parse`].
+impl Display for CborPath {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for CborPath {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parser::parse(s)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) struct AbsolutePath(Vec<Segment>);
 
@@ -433,7 +802,19 @@ impl AbsolutePath {
         current_values
     }
 
-    pub fn get_paths<'a>(&self, root: &'a Cbor) -> Vec<Path> {
+    #[inline]
+    pub fn read_iter<'p, 'a: 'p>(&'p self, root: &'a Cbor) -> NodeIter<'p, 'a> {
+        read_segments_iter(&self.0, root, root)
+    }
+
+    pub fn get_paths(&self, root: &Cbor) -> Vec<Path> {
+        self.read_with_paths(root)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    pub fn read_with_paths<'a>(&self, root: &'a Cbor) -> Vec<(Path, &'a Cbor)> {
         let mut current_values: Vec<&'a Cbor>;
         let mut current_path: Vec<Path>;
         let mut iter = self.0.iter();
@@ -441,7 +822,7 @@ impl AbsolutePath {
         if let Some(first) = iter.next() {
             (current_values, current_path) = first.get_paths(root, &[root], &[Path::default()]);
         } else {
-            return vec![Path::default()];
+            return vec![(Path::default(), root)];
         }
 
         for segment in iter {
@@ -449,7 +830,7 @@ impl AbsolutePath {
                 segment.get_paths(root, &current_values, &current_path);
         }
 
-        current_path
+        current_path.into_iter().zip(current_values).collect()
     }
 }
 
@@ -461,6 +842,11 @@ impl RelativePath {
         Self(segments)
     }
 
+    #[inline]
+    pub fn get_segments(&self) -> &Vec<Segment> {
+        &self.0
+    }
+
     pub fn evaluate<'a>(&self, root: &'a Cbor, current: &'a Cbor) -> Vec<&'a Cbor> {
         let mut current_values: Vec<&'a Cbor>;
         let mut iter = self.0.iter();
@@ -477,6 +863,31 @@ impl RelativePath {
 
         current_values
     }
+
+    #[inline]
+    pub fn evaluate_iter<'p, 'a: 'p>(
+        &'p self,
+        root: &'a Cbor,
+        current: &'a Cbor,
+    ) -> NodeIter<'p, 'a> {
+        read_segments_iter(&self.0, root, current)
+    }
+}
+
+/// Lazy list of nodes of a document living for `'a`,
+/// which may borrow the evaluated path for `'p`
+pub(crate) type NodeIter<'p, 'a> = Box<dyn Iterator<Item = &'a Cbor> + 'p>;
+
+fn read_segments_iter<'p, 'a: 'p>(
+    segments: &'p [Segment],
+    root: &'a Cbor,
+    current: &'a Cbor,
+) -> NodeIter<'p, 'a> {
+    segments
+        .iter()
+        .fold(Box::new(iter::once(current)), |current_values, segment| {
+            segment.read_iter(root, current_values)
+        })
 }
 
 #[derive(Debug, PartialEq)]
@@ -495,6 +906,18 @@ impl FilterPath {
             FilterPath::Rel(path) => path.evaluate(root, current),
         }
     }
+
+    #[inline]
+    pub fn evaluate_iter<'p, 'a: 'p>(
+        &'p self,
+        root: &'a Cbor,
+        current: &'a Cbor,
+    ) -> NodeIter<'p, 'a> {
+        match self {
+            FilterPath::Abs(path) => path.read_iter(root),
+            FilterPath::Rel(path) => path.evaluate_iter(root, current),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -526,6 +949,29 @@ impl Segment {
         }
     }
 
+    fn read_iter<'p, 'a: 'p>(
+        &'p self,
+        root: &'a Cbor,
+        current_values: NodeIter<'p, 'a>,
+    ) -> NodeIter<'p, 'a> {
+        match self {
+            Segment::Child(selectors) => Box::new(current_values.flat_map(move |current| {
+                selectors
+                    .iter()
+                    .flat_map(move |s| s.read_iter(root, current))
+            })),
+            Segment::Descendant(selectors) => Box::new(
+                current_values
+                    .flat_map(|value| iter::once(value).chain(Self::descendants_iter(value)))
+                    .flat_map(move |current| {
+                        selectors
+                            .iter()
+                            .flat_map(move |s| s.read_iter(root, current))
+                    }),
+            ),
+        }
+    }
+
     fn get_paths<'a>(
         &self,
         root: &'a Cbor,
@@ -581,6 +1027,17 @@ impl Segment {
         }
     }
 
+    /// Same order as [`fetch_descendants`](Segment::fetch_descendants)
+    fn descendants_iter(value: &Cbor) -> NodeIter<'_, '_> {
+        Box::new(
+            WildcardSelector.read_iter(value).chain(
+                WildcardSelector
+                    .read_iter(value)
+                    .flat_map(Self::descendants_iter),
+            ),
+        )
+    }
+
     fn fetch_descendants_with_paths<'a>(
         descendants: &mut Vec<(&'a Cbor, Path)>,
         value: &'a Cbor,
@@ -616,6 +1073,10 @@ pub(crate) enum Selector {
     Slice(SliceSelector),
     /// {"?", []}
     Filter(FilterSelector),
+    /// {"tag", tag}
+    Tag(TagSelector),
+    /// {"embedded", 1}
+    Embedded,
 }
 
 impl Selector {
@@ -626,6 +1087,20 @@ impl Selector {
             Selector::Index(selector) => selector.read(current),
             Selector::Slice(selector) => selector.read(current),
             Selector::Filter(filter) => filter.read(root, current),
+            Selector::Tag(selector) => selector.read(current),
+            Selector::Embedded => embedded(current).into_iter().collect(),
+        }
+    }
+
+    fn read_iter<'p, 'a: 'p>(&'p self, root: &'a Cbor, current: &'a Cbor) -> NodeIter<'p, 'a> {
+        match self {
+            Selector::Key(selector) => Box::new(selector.read_single(current).into_iter()),
+            Selector::Wildcard => WildcardSelector.read_iter(current),
+            Selector::Index(selector) => Box::new(selector.read_single(current).into_iter()),
+            Selector::Slice(selector) => Box::new(selector.read(current).into_iter()),
+            Selector::Filter(filter) => filter.read_iter(root, current),
+            Selector::Tag(selector) => selector.read_iter(current),
+            Selector::Embedded => Box::new(embedded(current).into_iter()),
         }
     }
 
@@ -641,6 +1116,10 @@ impl Selector {
             Selector::Index(selector) => selector.get_paths(current, current_path),
             Selector::Slice(selector) => selector.get_paths(current, current_path),
             Selector::Filter(filter) => filter.get_paths(root, current, current_path),
+            Selector::Tag(selector) => selector.get_paths(current, current_path),
+            Selector::Embedded => embedded(current)
+                .map(|v| (vec![v], vec![current_path.child_from_embedded()]))
+                .unwrap_or_else(|| (Vec::new(), Vec::new())),
         }
     }
 }
@@ -654,11 +1133,16 @@ impl KeySelector {
         Self(value)
     }
 
+    #[inline]
+    pub fn get_key(&self) -> &Cbor {
+        &self.0
+    }
+
     #[inline]
     fn read<'a>(&self, value: &'a Cbor) -> Vec<&'a Cbor> {
         self.read_single(value)
             .map(|v| vec![v])
-            .unwrap_or_else(Vec::new)
+            .unwrap_or_default()
     }
 
     #[inline]
@@ -666,7 +1150,7 @@ impl KeySelector {
         let Self(key) = &self;
         match value.kind() {
             ItemKind::Dict(mut d) => {
-                d.find_map(|(k, v)| if value_equals(k, key) { Some(v) } else { None })
+                d.find_map(|(k, v)| if key_equals(k, key) { Some(v) } else { None })
             }
             _ => None,
         }
@@ -692,6 +1176,15 @@ impl WildcardSelector {
         }
     }
 
+    #[inline]
+    fn read_iter<'a>(&self, value: &'a Cbor) -> NodeIter<'a, 'a> {
+        match value.kind() {
+            ItemKind::Dict(d) => Box::new(d.map(|(_, v)| v)),
+            ItemKind::Array(array) => Box::new(array),
+            _ => Box::new(iter::empty()),
+        }
+    }
+
     fn get_paths<'a>(&self, value: &'a Cbor, path: &Path) -> (Vec<&'a Cbor>, Vec<Path>) {
         match value.kind() {
             ItemKind::Dict(d) => d.map(|(k, v)| (v, path.child_from_key(k))).unzip(),
@@ -713,11 +1206,16 @@ impl IndexSelector {
         Self(index)
     }
 
+    #[inline]
+    pub fn get_index(&self) -> isize {
+        self.0
+    }
+
     #[inline]
     fn read<'a>(&self, value: &'a Cbor) -> Vec<&'a Cbor> {
         self.read_single(value)
             .map(|v| vec![v])
-            .unwrap_or_else(Vec::new)
+            .unwrap_or_default()
     }
 
     #[inline]
@@ -749,90 +1247,78 @@ impl IndexSelector {
 }
 
 #[derive(Debug, PartialEq)]
-pub(crate) struct SliceSelector(isize, isize, isize);
+pub(crate) struct SliceSelector(Option<isize>, Option<isize>, isize);
 
 impl SliceSelector {
     #[inline]
-    pub fn new(start: isize, end: isize, step: isize) -> Self {
+    pub fn new(start: Option<isize>, end: Option<isize>, step: isize) -> Self {
         Self(start, end, step)
     }
 
+    /// Returns `start`, `end` and `step`
+    #[inline]
+    pub fn get_bounds(&self) -> (Option<isize>, Option<isize>, isize) {
+        (self.0, self.1, self.2)
+    }
+
     fn read<'a>(&self, value: &'a Cbor) -> Vec<&'a Cbor> {
-        let SliceSelector(start, end, step) = &self;
         match value.kind() {
             ItemKind::Array(array) => {
-                let len = array.size().unwrap_or(array.count() as u64) as usize;
-                let start = normalize_index(*start, len);
-                let end = normalize_index(*end, len);
-                let step = *step;
-
-                if step > 0 {
-                    let start = usize::min(start as usize, len);
-                    let end = usize::min(end as usize, len);
-                    array
-                        .skip(start)
-                        .take(end - start)
-                        .step_by(step as usize)
-                        .collect()
-                } else {
-                    let actual_start = usize::min(
-                        (end + 1 + (start % -step) - ((end + 1) % step)) as usize,
-                        len,
-                    );
-                    let actual_end = usize::min((start + 1) as usize, len);
-                    let mut result: Vec<&'a Cbor> = array
-                        .skip(actual_start)
-                        .take(actual_end - actual_start)
-                        .step_by(-step as usize)
-                        .collect();
-                    result.reverse();
-                    result
-                }
+                let items: Vec<&'a Cbor> = array.collect();
+                self.indices(items.len()).map(|i| items[i]).collect()
             }
             _ => Vec::new(),
         }
     }
 
     fn get_paths<'a>(&self, value: &'a Cbor, path: &Path) -> (Vec<&'a Cbor>, Vec<Path>) {
-        let SliceSelector(start, end, step) = &self;
         match value.kind() {
             ItemKind::Array(array) => {
-                let len = array.size().unwrap_or(array.count() as u64) as usize;
-                let start = normalize_index(*start, len);
-                let end = normalize_index(*end, len);
-                let step = *step;
-
-                if step > 0 {
-                    let start = usize::min(start as usize, len);
-                    let end = usize::min(end as usize, len);
-                    array
-                        .enumerate()
-                        .skip(start)
-                        .take(end - start)
-                        .step_by(step as usize)
-                        .map(|(i, v)| (v, path.child_from_idx(i)))
-                        .unzip()
-                } else {
-                    let actual_start = usize::min(
-                        (end + 1 + (start % -step) - ((end + 1) % step)) as usize,
-                        len,
-                    );
-                    let actual_end = usize::min((start + 1) as usize, len);
-                    let (mut values, mut paths): (Vec<&'a Cbor>, Vec<Path>) = array
-                        .enumerate()
-                        .skip(actual_start)
-                        .take(actual_end - actual_start)
-                        .step_by(-step as usize)
-                        .map(|(i, v)| (v, path.child_from_idx(i)))
-                        .unzip();
-                    values.reverse();
-                    paths.reverse();
-                    (values, paths)
-                }
+                let items: Vec<&'a Cbor> = array.collect();
+                self.indices(items.len())
+                    .map(|i| (items[i], path.child_from_idx(i)))
+                    .unzip()
             }
             _ => (Vec::new(), Vec::new()),
         }
     }
+
+    /// Indices selected in an array of length `len`
+    ///
+    /// cf. https://www.rfc-editor.org/rfc/rfc9535.html#name-normative-semantics
+    fn indices(&self, len: usize) -> impl Iterator<Item = usize> {
+        let SliceSelector(start, end, step) = *self;
+        let len = len as isize;
+
+        let (mut i, lower, upper) = if step >= 0 {
+            let start = start.map(|s| normalize_index(s, len as usize)).unwrap_or(0);
+            let end = end.map(|e| normalize_index(e, len as usize)).unwrap_or(len);
+            let lower = start.clamp(0, len);
+            let upper = end.clamp(0, len);
+            (lower, lower, upper)
+        } else {
+            let start = start
+                .map(|s| normalize_index(s, len as usize))
+                .unwrap_or(len - 1);
+            let end = end
+                .map(|e| normalize_index(e, len as usize))
+                .unwrap_or(-len - 1);
+            let upper = start.clamp(-1, len - 1);
+            let lower = end.clamp(-1, len - 1);
+            (upper, lower, upper)
+        };
+
+        std::iter::from_fn(move || {
+            let in_bounds = if step > 0 { i < upper } else { step < 0 && lower < i };
+            if in_bounds {
+                let current = i;
+                i = i.saturating_add(step);
+                Some(current as usize)
+            } else {
+                None
+            }
+        })
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -844,6 +1330,11 @@ impl FilterSelector {
         Self(boolean_expr)
     }
 
+    #[inline]
+    pub fn get_boolean_expr(&self) -> &BooleanExpr {
+        &self.0
+    }
+
     #[inline]
     fn read<'a>(&self, root: &'a Cbor, current: &'a Cbor) -> Vec<&'a Cbor> {
         let Self(boolean_expr) = &self;
@@ -862,6 +1353,18 @@ impl FilterSelector {
         }
     }
 
+    fn read_iter<'p, 'a: 'p>(&'p self, root: &'a Cbor, current: &'a Cbor) -> NodeIter<'p, 'a> {
+        let Self(boolean_expr) = &self;
+        match current.kind() {
+            ItemKind::Array(a) => Box::new(a.filter(move |v| boolean_expr.read(root, v))),
+            ItemKind::Dict(d) => Box::new(
+                d.map(|(_, v)| v)
+                    .filter(move |v| boolean_expr.read(root, v)),
+            ),
+            _ => Box::new(iter::empty()),
+        }
+    }
+
     fn get_paths<'a>(
         &self,
         root: &'a Cbor,
@@ -894,6 +1397,58 @@ impl FilterSelector {
     }
 }
 
+/// Selects the children of a map or an array whose outermost tag is the selector tag
+#[derive(Debug, PartialEq)]
+pub(crate) struct TagSelector(u64);
+
+impl TagSelector {
+    #[inline]
+    pub fn new(tag: u64) -> Self {
+        Self(tag)
+    }
+
+    #[inline]
+    pub fn get_tag(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn read<'a>(&self, value: &'a Cbor) -> Vec<&'a Cbor> {
+        self.read_iter(value).collect()
+    }
+
+    fn read_iter<'a>(&self, value: &'a Cbor) -> NodeIter<'a, 'a> {
+        let tag = self.0;
+        Box::new(
+            WildcardSelector
+                .read_iter(value)
+                .filter(move |v| v.tags().first() == Some(tag)),
+        )
+    }
+
+    fn get_paths<'a>(&self, value: &'a Cbor, path: &Path) -> (Vec<&'a Cbor>, Vec<Path>) {
+        let (values, paths) = WildcardSelector.get_paths(value, path);
+        values
+            .into_iter()
+            .zip(paths)
+            .filter(|(v, _)| v.tags().first() == Some(self.0))
+            .unzip()
+    }
+}
+
+/// Decodes the `CBOR` item embedded in a byte string, untagged or with tag 24
+///
+/// Returns [`None`] if `value` is not such a byte string, if it is chunked,
+/// or if its content is not a valid `CBOR` item.
+pub(crate) fn embedded(value: &Cbor) -> Option<&Cbor> {
+    match (value.kind(), value.tags().first()) {
+        (ItemKind::Bytes(bytes), None | Some(TAG_CBOR_ITEM)) => {
+            Cbor::checked(bytes.as_slice()?).ok()
+        }
+        _ => None,
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum BooleanExpr {
     Or(Box<BooleanExpr>, Box<BooleanExpr>),
@@ -903,6 +1458,7 @@ pub(crate) enum BooleanExpr {
     /// path existence or non-existence
     Path(FilterPath),
     Function(Function),
+    Membership(MembershipExpr),
 }
 
 impl BooleanExpr {
@@ -913,8 +1469,9 @@ impl BooleanExpr {
             BooleanExpr::And(l, r) => l.read(root, current) && r.read(root, current),
             BooleanExpr::Not(e) => !e.read(root, current),
             BooleanExpr::Comparison(c) => c.read(root, current),
-            BooleanExpr::Path(p) => !p.evaluate(root, current).is_empty(),
+            BooleanExpr::Path(p) => p.evaluate_iter(root, current).next().is_some(),
             BooleanExpr::Function(f) => f.read_as_boolean_expr(root, current),
+            BooleanExpr::Membership(m) => m.read(root, current),
         }
     }
 }
@@ -928,6 +1485,21 @@ impl ComparisonExpr {
         Self(left, operator, right)
     }
 
+    #[inline]
+    pub fn get_left(&self) -> &Comparable {
+        &self.0
+    }
+
+    #[inline]
+    pub fn get_operator(&self) -> &ComparisonOperator {
+        &self.1
+    }
+
+    #[inline]
+    pub fn get_right(&self) -> &Comparable {
+        &self.2
+    }
+
     pub fn read(&self, root: &Cbor, current: &Cbor) -> bool {
         let ComparisonExpr(left, op, right) = &self;
         match op {
@@ -972,12 +1544,26 @@ impl Comparable {
         let v1 = v1.as_ref().map(|v| v.as_ref());
         let v2 = v2.as_ref().map(|v| v.as_ref());
 
+        match (v1.and_then(timestamp), v2.and_then(timestamp)) {
+            (Some(t1), Some(t2)) => return t1 < t2,
+            (None, None) => (),
+            // an epoch-based date/time is still compared numerically with a plain number
+            (Some(_), None) if v2.is_some_and(is_untagged_number) => (),
+            (None, Some(_)) if v1.is_some_and(is_untagged_number) => (),
+            // otherwise a date/time value is only comparable with another date/time value
+            _ => return false,
+        }
+
+        if let (Some(n1), Some(n2)) = (
+            v1.and_then(Number::from_cbor),
+            v2.and_then(Number::from_cbor),
+        ) {
+            return n1 < n2;
+        }
+
         match (v1.map(|v| v.kind()), v2.map(|v| v.kind())) {
-            (Some(ItemKind::Pos(v1)), Some(ItemKind::Pos(v2))) => v1 < v2,
-            (Some(ItemKind::Neg(v1)), Some(ItemKind::Neg(v2))) => v1 < v2,
-            (Some(ItemKind::Float(v1)), Some(ItemKind::Float(v2))) => v1 < v2,
-            (Some(ItemKind::Bytes(v1)), Some(ItemKind::Bytes(v2))) => v1 < v2,
-            (Some(ItemKind::Str(v1)), Some(ItemKind::Str(v2))) => v1 < v2,
+            (Some(ItemKind::Bytes(v1)), Some(ItemKind::Bytes(v2))) => v1.as_cow() < v2.as_cow(),
+            (Some(ItemKind::Str(v1)), Some(ItemKind::Str(v2))) => v1.as_cow() < v2.as_cow(),
             _ => false,
         }
     }
@@ -993,27 +1579,66 @@ impl Comparable {
     }
 }
 
+/// Instant of a date/time value (tag 0 or 1), as seconds and nanoseconds since the Unix epoch
+fn timestamp(value: &Cbor) -> Option<(i64, u32)> {
+    match value.tags().single() {
+        Some(TAG_ISO8601 | TAG_EPOCH) => match value.decode() {
+            CborValue::Timestamp(t) => Some((t.unix_epoch(), t.nanos())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Integer or float without tag
+fn is_untagged_number(value: &Cbor) -> bool {
+    value.tags().is_empty()
+        && matches!(
+            value.kind(),
+            ItemKind::Pos(_) | ItemKind::Neg(_) | ItemKind::Float(_)
+        )
+}
+
+/// Map keys are only equal if they are of the same kind (e.g. `1` and `1.0` are different keys)
+fn key_equals(k1: &Cbor, k2: &Cbor) -> bool {
+    mem::discriminant(&k1.kind()) == mem::discriminant(&k2.kind()) && value_equals(k1, k2)
+}
+
 fn value_equals(v1: &Cbor, v2: &Cbor) -> bool {
+    match (timestamp(v1), timestamp(v2)) {
+        (Some(t1), Some(t2)) => return t1 == t2,
+        (None, None) => (),
+        (Some(_), None) if is_untagged_number(v2) => (),
+        (None, Some(_)) if is_untagged_number(v1) => (),
+        _ => return false,
+    }
+
+    if let (Some(n1), Some(n2)) = (Number::from_cbor(v1), Number::from_cbor(v2)) {
+        return n1 == n2;
+    }
+
     match (v1.kind(), v2.kind()) {
-        (ItemKind::Pos(v1), ItemKind::Pos(v2)) => v1 == v2,
-        (ItemKind::Neg(v1), ItemKind::Neg(v2)) => v1 == v2,
         (ItemKind::Bool(v1), ItemKind::Bool(v2)) => v1 == v2,
         (ItemKind::Simple(v1), ItemKind::Simple(v2)) => v1 == v2,
-        (ItemKind::Float(v1), ItemKind::Float(v2)) => v1 == v2,
-        (ItemKind::Bytes(v1), ItemKind::Bytes(v2)) => v1 == v2,
-        (ItemKind::Str(v1), ItemKind::Str(v2)) => v1 == v2,
+        // strings are compared by content, whether they are chunked or not
+        (ItemKind::Bytes(v1), ItemKind::Bytes(v2)) => v1.as_cow() == v2.as_cow(),
+        (ItemKind::Str(v1), ItemKind::Str(v2)) => v1.as_cow() == v2.as_cow(),
         (ItemKind::Null, ItemKind::Null) => true,
         (ItemKind::Array(a1), ItemKind::Array(a2)) => {
             let len1 = a1.size().unwrap_or(a1.count() as u64);
             let len2 = a2.size().unwrap_or(a2.count() as u64);
-            len1 == len2 && a1.zip(a2).all(|(v1, v2)| v1 == v2)
+            len1 == len2 && a1.zip(a2).all(|(v1, v2)| value_equals(v1, v2))
         }
-        (ItemKind::Dict(mut d1), ItemKind::Dict(mut d2)) => {
+        (ItemKind::Dict(mut d1), ItemKind::Dict(d2)) => {
             let len1 = d1.size().unwrap_or(d1.count() as u64);
             let len2 = d2.size().unwrap_or(d2.count() as u64);
             len1 == len2
                 && d1.all(|(key, v1)| {
-                    if let Some(v2) = d2.find_map(|(k, v)| if k == key { Some(v) } else { None }) {
+                    // search from the start of the map for each key
+                    let mut d2 = d2;
+                    if let Some(v2) =
+                        d2.find_map(|(k, v)| if key_equals(k, key) { Some(v) } else { None })
+                    {
                         value_equals(v1, v2)
                     } else {
                         false
@@ -1034,6 +1659,93 @@ pub(crate) enum ComparisonOperator {
     Lte,
 }
 
+/// Membership of a value in an array, either a literal array or an array reached through a singular path
+///
+/// The array is on the left of `contains` and on the right of `in` and `nin`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct MembershipExpr(Comparable, MembershipOperator, Comparable);
+
+impl MembershipExpr {
+    #[inline]
+    pub fn new(value: Comparable, operator: MembershipOperator, array: Comparable) -> Self {
+        Self(value, operator, array)
+    }
+
+    #[inline]
+    pub fn get_value(&self) -> &Comparable {
+        &self.0
+    }
+
+    #[inline]
+    pub fn get_operator(&self) -> &MembershipOperator {
+        &self.1
+    }
+
+    #[inline]
+    pub fn get_array(&self) -> &Comparable {
+        &self.2
+    }
+
+    pub fn read(&self, root: &Cbor, current: &Cbor) -> bool {
+        let MembershipExpr(value, op, array) = &self;
+        let is_member = match (value.read(root, current), array.read(root, current)) {
+            (Some(value), Some(array)) => array_contains(&array, &value),
+            _ => false,
+        };
+        match op {
+            MembershipOperator::In | MembershipOperator::Contains => is_member,
+            MembershipOperator::Nin => !is_member,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MembershipOperator {
+    /// `value in array`
+    In,
+    /// `value nin array`
+    Nin,
+    /// `array contains value`
+    Contains,
+}
+
+impl MembershipOperator {
+    /// Keyword of the operator in the text syntax
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MembershipOperator::In => "in",
+            MembershipOperator::Nin => "nin",
+            MembershipOperator::Contains => "contains",
+        }
+    }
+
+    /// Identifier of the operator in the `CBOR` syntax,
+    /// where `contains` is already the substring test
+    pub fn identifier(self) -> &'static str {
+        match self {
+            MembershipOperator::Contains => "array_contains",
+            _ => self.as_str(),
+        }
+    }
+
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "in" => Some(MembershipOperator::In),
+            "nin" => Some(MembershipOperator::Nin),
+            "array_contains" => Some(MembershipOperator::Contains),
+            _ => None,
+        }
+    }
+}
+
+/// Checks if `array` is an array with an element equal to `value`
+fn array_contains(array: &Cbor, value: &Cbor) -> bool {
+    match array.kind() {
+        ItemKind::Array(mut elements) => elements.any(|element| value_equals(element, value)),
+        _ => false,
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum SingularPath {
     /// Absolute singular path (beginning by '$')
@@ -1083,8 +1795,93 @@ impl SingularSegment {
 pub(crate) enum Function {
     Length(Box<Comparable>),
     Count(FilterPath),
-    Regex(Box<Comparable>, Regex),
-    Value(FilterPath)
+    /// Regular expression full match
+    Match(Box<Comparable>, RegexArgument),
+    /// Regular expression substring match
+    Search(Box<Comparable>, RegexArgument),
+    Value(FilterPath),
+    /// Outermost tag of a value
+    Tag(Box<Comparable>),
+    /// Arithmetic expression between two numbers
+    Arithmetic(ArithmeticOperator, Box<Comparable>, Box<Comparable>),
+    /// `abs`, `floor`, `ceil` or `round` of a number
+    Numeric(NumericFunction, Box<Comparable>),
+    /// `starts_with`, `ends_with` or `contains` test between two strings
+    StringTest(StringTest, Box<Comparable>, Box<Comparable>),
+    /// `lower` or `upper` case conversion of a text string
+    CaseConversion(CaseConversion, Box<Comparable>),
+    /// Part of a string, between a start index and an optional end index
+    Substring(Box<Comparable>, Box<Comparable>, Option<Box<Comparable>>),
+    /// Concatenation of strings
+    Concat(Vec<Comparable>),
+    /// Function registered in a [`FunctionRegistry`]
+    User(Arc<FunctionDefinition>, Vec<Argument>),
+}
+
+/// Argument of a user-defined function, matching the type of its parameter
+#[derive(Debug, PartialEq)]
+pub(crate) enum Argument {
+    Value(Comparable),
+    Logical(BooleanExpr),
+    Nodes(FilterPath),
+}
+
+impl Argument {
+    fn evaluate<'a>(&'a self, root: &'a Cbor, current: &'a Cbor) -> FunctionArgument<'a> {
+        match self {
+            Argument::Value(comparable) => FunctionArgument::Value(comparable.read(root, current)),
+            Argument::Logical(expr) => FunctionArgument::Logical(expr.read(root, current)),
+            Argument::Nodes(path) => FunctionArgument::Nodes(path.evaluate(root, current)),
+        }
+    }
+}
+
+/// Regular expression argument of the `match` and `search` functions
+#[derive(Debug, PartialEq)]
+pub(crate) enum RegexArgument {
+    /// String literal, validated when the expression is built
+    Literal(IRegexp),
+    /// Any other comparable, evaluated against the document
+    Comparable(Box<Comparable>),
+}
+
+impl RegexArgument {
+    /// Compiles `comparable` if it is a text string literal
+    pub fn new(comparable: Comparable, full_match: bool) -> Result<Self, Error> {
+        match &comparable {
+            Comparable::Value(value) if value.tags().is_empty() => {
+                if let ItemKind::Str(pattern) = value.kind() {
+                    let pattern = pattern.as_cow();
+                    return Ok(RegexArgument::Literal(if full_match {
+                        IRegexp::full_match(&pattern)?
+                    } else {
+                        IRegexp::substring_match(&pattern)?
+                    }));
+                }
+            }
+            _ => (),
+        }
+        Ok(RegexArgument::Comparable(Box::new(comparable)))
+    }
+
+    fn is_match(&self, value: &str, full_match: bool, root: &Cbor, current: &Cbor) -> bool {
+        match self {
+            RegexArgument::Literal(regex) => regex.is_match(value),
+            RegexArgument::Comparable(comparable) => {
+                let pattern = comparable.read(root, current);
+                let pattern = pattern.as_ref().map(|p| p.as_ref());
+                let regex = match pattern.map(|p| p.kind()) {
+                    Some(ItemKind::Str(pattern)) if full_match => {
+                        IRegexp::full_match(&pattern.as_cow())
+                    }
+                    Some(ItemKind::Str(pattern)) => IRegexp::substring_match(&pattern.as_cow()),
+                    _ => return false,
+                };
+                // a pattern which is not a valid I-Regexp never matches
+                regex.is_ok_and(|regex| regex.is_match(value))
+            }
+        }
+    }
 }
 
 impl PartialEq for Function {
@@ -1093,7 +1890,22 @@ impl PartialEq for Function {
             (Self::Length(l0), Self::Length(r0)) => l0 == r0,
             (Self::Count(l0), Self::Count(r0)) => l0 == r0,
             (Self::Value(l0), Self::Value(r0)) => l0 == r0,
-            (Self::Regex(l0, l1), Self::Regex(r0, r1)) => l0 == r0 && l1.as_str() == r1.as_str(),
+            (Self::Tag(l0), Self::Tag(r0)) => l0 == r0,
+            (Self::Match(l0, l1), Self::Match(r0, r1)) => l0 == r0 && l1 == r1,
+            (Self::Search(l0, l1), Self::Search(r0, r1)) => l0 == r0 && l1 == r1,
+            (Self::Arithmetic(l0, l1, l2), Self::Arithmetic(r0, r1, r2)) => {
+                l0 == r0 && l1 == r1 && l2 == r2
+            }
+            (Self::Numeric(l0, l1), Self::Numeric(r0, r1)) => l0 == r0 && l1 == r1,
+            (Self::StringTest(l0, l1, l2), Self::StringTest(r0, r1, r2)) => {
+                l0 == r0 && l1 == r1 && l2 == r2
+            }
+            (Self::CaseConversion(l0, l1), Self::CaseConversion(r0, r1)) => l0 == r0 && l1 == r1,
+            (Self::Substring(l0, l1, l2), Self::Substring(r0, r1, r2)) => {
+                l0 == r0 && l1 == r1 && l2 == r2
+            }
+            (Self::Concat(l0), Self::Concat(r0)) => l0 == r0,
+            (Self::User(l0, l1), Self::User(r0, r1)) => l0.name() == r0.name() && l1 == r1,
             _ => false,
         }
     }
@@ -1102,17 +1914,28 @@ impl PartialEq for Function {
 impl Function {
     fn read_as_boolean_expr(&self, root: &Cbor, current: &Cbor) -> bool {
         match self {
-            Function::Regex(comparable, regex) => {
+            Function::Match(comparable, regex) | Function::Search(comparable, regex) => {
                 let value = comparable.read(root, current);
                 let value = value.as_ref().map(|v| v.as_ref());
                 match value.map(|v| v.kind()) {
-                    Some(ItemKind::Str(str)) => match str.as_str() {
-                        Some(str) => regex.is_match(str),
-                        None => false,
-                    },
+                    Some(ItemKind::Str(str)) => regex.is_match(
+                        &str.as_cow(),
+                        matches!(self, Function::Match(..)),
+                        root,
+                        current,
+                    ),
                     _ => false,
                 }
             }
+            Function::StringTest(test, value, pattern) => {
+                match (value.read(root, current), pattern.read(root, current)) {
+                    (Some(value), Some(pattern)) => test.apply(&value, &pattern),
+                    _ => false,
+                }
+            }
+            Function::User(definition, arguments) => {
+                definition.call_as_logical(&Self::evaluate_arguments(arguments, root, current))
+            }
             _ => false,
         }
     }
@@ -1132,13 +1955,14 @@ impl Function {
                         Some(CborBuilder::new().write_pos(len, None))
                     }
                     Some(ItemKind::Str(s)) => {
-                        Some(CborBuilder::new().write_pos(s.len() as u64, None))
+                        // number of Unicode scalar values, each chunk being valid UTF-8 on its own
+                        let len = s.map(|chunk| chunk.chars().count()).sum::<usize>();
+                        Some(CborBuilder::new().write_pos(len as u64, None))
                     }
                     Some(ItemKind::Bytes(b)) => {
                         Some(CborBuilder::new().write_pos(b.len() as u64, None))
                     }
-                    None => None,
-                    _ => Some(CborBuilder::new().write_pos(1, None)),
+                    _ => None,
                 }
             }
             Function::Count(path) => {
@@ -1153,9 +1977,60 @@ impl Function {
                 }
 
             },
+            Function::Tag(comparable) => {
+                let value = comparable.read(root, current)?;
+                let tag = value.tags().first()?;
+                Some(CborBuilder::new().write_pos(tag, None))
+            }
+            Function::Arithmetic(operator, left, right) => {
+                let left = left.read(root, current)?;
+                let right = right.read(root, current)?;
+                operator.apply(&left, &right)
+            }
+            Function::Numeric(function, comparable) => {
+                function.apply(&comparable.read(root, current)?)
+            }
+            Function::CaseConversion(conversion, comparable) => {
+                conversion.apply(&comparable.read(root, current)?)
+            }
+            Function::Substring(comparable, start, end) => {
+                let value = comparable.read(root, current)?;
+                let start = start.read(root, current)?;
+                let end = match end {
+                    Some(end) => Some(end.read(root, current)?),
+                    None => None,
+                };
+                strings::substring(&value, &start, end.as_deref())
+            }
+            Function::Concat(comparables) => {
+                let values = comparables
+                    .iter()
+                    .map(|comparable| comparable.read(root, current))
+                    .collect::<Option<Vec<_>>>()?;
+                strings::concat(
+                    &values
+                        .iter()
+                        .map(|value| value.as_ref())
+                        .collect::<Vec<_>>(),
+                )
+            }
+            Function::User(definition, arguments) => {
+                definition.call_as_value(&Self::evaluate_arguments(arguments, root, current))
+            }
             _ => None,
         }
     }
+
+    fn evaluate_arguments<'a>(
+        arguments: &'a [Argument],
+        root: &'a Cbor,
+        current: &'a Cbor,
+    ) -> Vec<FunctionArgument<'a>> {
+        arguments
+            .iter()
+            .map(|argument| argument.evaluate(root, current))
+            .collect()
+    }
 }
 
 #[inline]
@@ -1163,6 +2038,6 @@ pub(crate) fn normalize_index(i: isize, len: usize) -> isize {
     if i >= 0 {
         i
     } else {
-        len as isize + i
+        (len as isize).saturating_add(i)
     }
 }
@@ -1,23 +1,39 @@
 use crate::{
-    builder, AbsolutePath, BooleanExpr, CborPath, Comparable, ComparisonExpr, ComparisonOperator,
-    Error, FilterPath, FilterSelector, Function, IndexSelector, KeySelector, RelativePath, Segment,
-    Selector, SingularPath, SingularSegment, SliceSelector,
+    arithmetic::{ArithmeticOperator, NumericFunction},
+    strings::{CaseConversion, StringTest},
+    type_check::TypeCheck,
+    AbsolutePath, Argument, BooleanExpr, CborPath, Comparable, ComparisonExpr, ComparisonOperator,
+    Error, FilterPath, FilterSelector, Function, FunctionRegistry, FunctionType, IndexSelector,
+    KeySelector, MembershipExpr, MembershipOperator, RegexArgument, RelativePath, Segment,
+    Selector, SingularPath, SingularSegment, SliceSelector, TagSelector,
 };
-use cbor_data::{ArrayIter, Cbor, ItemKind};
+use cbor_data::{ArrayIter, Cbor, ItemKind, Writer};
 
 impl TryFrom<&Cbor> for CborPath {
     type Error = Error;
 
     fn try_from(value: &Cbor) -> Result<Self, Self::Error> {
-        let segments: Segments = (value, true).try_into()?;
+        (value, &FunctionRegistry::new()).try_into()
+    }
+}
+
+impl TryFrom<(&Cbor, &FunctionRegistry)> for CborPath {
+    type Error = Error;
+
+    fn try_from(value: (&Cbor, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (value, registry) = value;
+        let segments: Segments = (value, true, registry).try_into()?;
+        segments.0.type_check()?;
         Ok(CborPath::new(segments.0))
     }
 }
 
-impl TryFrom<&Cbor> for FilterPath {
+impl TryFrom<(&Cbor, &FunctionRegistry)> for FilterPath {
     type Error = Error;
 
-    fn try_from(value: &Cbor) -> Result<Self, Self::Error> {
+    fn try_from(value: (&Cbor, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (value, registry) = value;
+
         match value.kind() {
             ItemKind::Str(identifier) => match identifier.as_str() {
                 Some("$") => Ok(FilterPath::Abs(AbsolutePath::new(vec![]))),
@@ -52,7 +68,7 @@ impl TryFrom<&Cbor> for FilterPath {
                 };
 
                 for value in values {
-                    let segment: SegmentForConversion = value.try_into()?;
+                    let segment: SegmentForConversion = (value, registry).try_into()?;
                     segments.push(segment.into_segment());
                 }
 
@@ -71,11 +87,11 @@ impl TryFrom<&Cbor> for FilterPath {
 
 struct Segments(Vec<Segment>);
 
-impl TryFrom<(&Cbor, bool)> for Segments {
+impl TryFrom<(&Cbor, bool, &FunctionRegistry)> for Segments {
     type Error = Error;
 
-    fn try_from(value: (&Cbor, bool)) -> Result<Self, Self::Error> {
-        let (value, absolute_path) = value;
+    fn try_from(value: (&Cbor, bool, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (value, absolute_path, registry) = value;
 
         match value.kind() {
             ItemKind::Str(identifier) => match identifier.as_str() {
@@ -113,7 +129,7 @@ impl TryFrom<(&Cbor, bool)> for Segments {
                 };
 
                 for value in values {
-                    let segment: SegmentForConversion = value.try_into()?;
+                    let segment: SegmentForConversion = (value, registry).try_into()?;
                     segments.push(segment.into_segment());
                 }
 
@@ -142,10 +158,12 @@ impl SegmentForConversion {
     }
 }
 
-impl TryFrom<&Cbor> for SegmentForConversion {
+impl TryFrom<(&Cbor, &FunctionRegistry)> for SegmentForConversion {
     type Error = Error;
 
-    fn try_from(value: &Cbor) -> Result<Self, Self::Error> {
+    fn try_from(value: (&Cbor, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (value, registry) = value;
+
         match value.kind() {
             ItemKind::Pos(_)
             | ItemKind::Neg(_)
@@ -159,7 +177,7 @@ impl TryFrom<&Cbor> for SegmentForConversion {
             ))),
             ItemKind::Array(a) => {
                 let selectors = a
-                    .map(|v| match v.try_into()? {
+                    .map(|v| match (v, registry).try_into()? {
                         SegmentForConversion::Selector(selector) => Ok(selector),
                         _ => Err(Error::Conversion("Expected a single selector".to_owned())),
                     })
@@ -176,7 +194,7 @@ impl TryFrom<&Cbor> for SegmentForConversion {
                 };
 
                 match identifier.as_str() {
-                    Some("..") => match value.try_into()? {
+                    Some("..") => match (value, registry).try_into()? {
                         SegmentForConversion::Selectors(selectors) => {
                             Ok(SegmentForConversion::Descendant(selectors))
                         }
@@ -189,7 +207,7 @@ impl TryFrom<&Cbor> for SegmentForConversion {
                         )),
                     },
                     Some("*") => match value.kind() {
-                        ItemKind::Pos(i) if i == 1 => {
+                        ItemKind::Pos(1) => {
                             Ok(SegmentForConversion::Selector(Selector::Wildcard))
                         }
                         _ => Err(Error::Conversion("Cannot parse wildcard".to_owned())),
@@ -201,10 +219,22 @@ impl TryFrom<&Cbor> for SegmentForConversion {
                         value.try_into()?,
                     ))),
                     Some("?") => Ok(SegmentForConversion::Selector(Selector::Filter(
-                        FilterSelector::new(value.try_into()?),
+                        FilterSelector::new((value, registry).try_into()?),
+                    ))),
+                    Some("tag") => Ok(SegmentForConversion::Selector(Selector::Tag(
+                        value.try_into()?,
+                    ))),
+                    Some("embedded") => match value.kind() {
+                        ItemKind::Pos(1) => Ok(SegmentForConversion::Selector(Selector::Embedded)),
+                        _ => Err(Error::Conversion("Cannot parse embedded".to_owned())),
+                    },
+                    // any key, e.g. an array or a map which would be read as a segment or a selector
+                    Some("key") => Ok(SegmentForConversion::Selector(Selector::Key(
+                        KeySelector::new(value.to_owned()),
                     ))),
                     _ => Err(Error::Conversion(
-                        "Expected identifier `..`, `#`, `:` or `?`".to_owned(),
+                        "Expected identifier `..`, `#`, `:`, `?`, `tag`, `embedded` or `key`"
+                            .to_owned(),
                     )),
                 }
             }
@@ -227,6 +257,17 @@ impl TryFrom<&Cbor> for IndexSelector {
     }
 }
 
+impl TryFrom<&Cbor> for TagSelector {
+    type Error = Error;
+
+    fn try_from(value: &Cbor) -> Result<Self, Self::Error> {
+        match value.kind() {
+            ItemKind::Pos(tag) => Ok(TagSelector::new(tag)),
+            _ => Err(Error::Conversion("Expected unsigned integer".to_owned())),
+        }
+    }
+}
+
 impl TryFrom<&Cbor> for SliceSelector {
     type Error = Error;
 
@@ -235,15 +276,21 @@ impl TryFrom<&Cbor> for SliceSelector {
             match (a.next(), a.next(), a.next(), a.next()) {
                 (Some(start), Some(end), Some(step), None) => {
                     let start = match start.kind() {
-                        ItemKind::Pos(index) => index as isize,
-                        ItemKind::Neg(index) => -1 - (index as isize),
-                        _ => return Err(Error::Conversion("Expected integer".to_owned())),
+                        ItemKind::Pos(index) => Some(index as isize),
+                        ItemKind::Neg(index) => Some(-1 - (index as isize)),
+                        ItemKind::Null => None,
+                        _ => {
+                            return Err(Error::Conversion("Expected integer or null".to_owned()))
+                        }
                     };
 
                     let end = match end.kind() {
-                        ItemKind::Pos(index) => index as isize,
-                        ItemKind::Neg(index) => -1 - (index as isize),
-                        _ => return Err(Error::Conversion("Expected integer".to_owned())),
+                        ItemKind::Pos(index) => Some(index as isize),
+                        ItemKind::Neg(index) => Some(-1 - (index as isize)),
+                        ItemKind::Null => None,
+                        _ => {
+                            return Err(Error::Conversion("Expected integer or null".to_owned()))
+                        }
                     };
 
                     let step = match step.kind() {
@@ -262,12 +309,14 @@ impl TryFrom<&Cbor> for SliceSelector {
     }
 }
 
-impl TryFrom<&Cbor> for BooleanExpr {
+impl TryFrom<(&Cbor, &FunctionRegistry)> for BooleanExpr {
     type Error = Error;
 
-    fn try_from(value: &Cbor) -> Result<Self, Self::Error> {
+    fn try_from(value: (&Cbor, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (value, registry) = value;
+
         match value.kind() {
-            ItemKind::Array(_) => Ok(BooleanExpr::Path(value.try_into()?)),
+            ItemKind::Array(_) => Ok(BooleanExpr::Path((value, registry).try_into()?)),
             ItemKind::Dict(mut d) => {
                 let (Some((identifier, value)), None) = (d.next(), d.next()) else {
                     return Err(Error::Conversion("Expected a single element map".to_owned()));
@@ -282,109 +331,21 @@ impl TryFrom<&Cbor> for BooleanExpr {
                 };
 
                 match identifier {
-                    "&&" => {
-                        if let ItemKind::Array(mut a) = value.kind() {
-                            match (a.next(), a.next(), a.next()) {
-                                (Some(left), Some(right), None) => Ok(BooleanExpr::And(
-                                    Box::new(left.try_into()?),
-                                    Box::new(right.try_into()?),
-                                )),
-                                _ => Err(Error::Conversion(format!(
-                                    "Cannot parse boolean expression from `{value:?}`"
-                                ))),
-                            }
-                        } else {
-                            Err(Error::Conversion(format!(
-                                "Cannot parse boolean expression from `{value:?}`"
-                            )))
-                        }
-                    }
-                    "||" => {
-                        if let ItemKind::Array(mut a) = value.kind() {
-                            match (a.next(), a.next(), a.next()) {
-                                (Some(left), Some(right), None) => Ok(BooleanExpr::Or(
-                                    Box::new(left.try_into()?),
-                                    Box::new(right.try_into()?),
-                                )),
-                                _ => Err(Error::Conversion(format!(
-                                    "Cannot parse boolean expression from `{value:?}`"
-                                ))),
-                            }
-                        } else {
-                            Err(Error::Conversion(format!(
-                                "Cannot parse boolean expression from `{value:?}`"
-                            )))
-                        }
-                    }
-                    "!" => Ok(BooleanExpr::Not(Box::new(value.try_into()?))),
-                    "<" | "<=" | "==" | "!=" | ">=" | ">" => {
-                        Ok(BooleanExpr::Comparison((identifier, value).try_into()?))
-                    }
-                    "match" => {
-                        if let ItemKind::Array(mut a) = value.kind() {
-                            match (a.next(), a.next(), a.next()) {
-                                (Some(comparable), Some(regex), None) => {
-                                    let ItemKind::Str(regex) = regex.kind() else {
-                                        return Err(Error::Conversion(format!(
-                                            "Cannot parse match function from `{value:?}`"
-                                        )));
-                                    };
-                                    let Some(regex) = regex.as_str() else {
-                                        return Err(Error::Conversion(format!(
-                                            "Cannot parse match function from `{value:?}`"
-                                        )));
-                                    };
-
-                                    Ok(builder::_match(
-                                        TryInto::<Comparable>::try_into(comparable)?,
-                                        regex,
-                                    )?
-                                    .build())
-                                }
-                                _ => Err(Error::Conversion(format!(
-                                    "Cannot parse match function from `{value:?}`"
-                                ))),
-                            }
-                        } else {
-                            Err(Error::Conversion(format!(
-                                "Cannot parse match function from `{value:?}`"
-                            )))
-                        }
-                    }
-                    "search" => {
-                        if let ItemKind::Array(mut a) = value.kind() {
-                            match (a.next(), a.next(), a.next()) {
-                                (Some(comparable), Some(regex), None) => {
-                                    let ItemKind::Str(regex) = regex.kind() else {
-                                        return Err(Error::Conversion(format!(
-                                            "Cannot parse search function from `{value:?}`"
-                                        )));
-                                    };
-                                    let Some(regex) = regex.as_str() else {
-                                        return Err(Error::Conversion(format!(
-                                            "Cannot parse search function from `{value:?}`"
-                                        )));
-                                    };
-
-                                    Ok(builder::search(
-                                        TryInto::<Comparable>::try_into(comparable)?,
-                                        regex,
-                                    )?
-                                    .build())
-                                }
-                                _ => Err(Error::Conversion(format!(
-                                    "Cannot parse search function from `{value:?}`"
-                                ))),
-                            }
-                        } else {
-                            Err(Error::Conversion(format!(
-                                "Cannot parse search function from `{value:?}`"
-                            )))
-                        }
-                    }
-                    _ => Err(Error::Conversion(format!(
-                        "Cannot parse boolean expression from `{value:?}`"
-                    ))),
+                    "&&" => logical_expr(value, registry, BooleanExpr::And),
+                    "||" => logical_expr(value, registry, BooleanExpr::Or),
+                    "!" => Ok(BooleanExpr::Not(Box::new((value, registry).try_into()?))),
+                    "<" | "<=" | "==" | "!=" | ">=" | ">" => Ok(BooleanExpr::Comparison(
+                        (identifier, value, registry).try_into()?,
+                    )),
+                    _ => match MembershipOperator::from_identifier(identifier) {
+                        Some(operator) => Ok(BooleanExpr::Membership(
+                            (operator, value, registry).try_into()?,
+                        )),
+                        // the type of the function is checked with the whole expression
+                        None => Ok(BooleanExpr::Function(
+                            (identifier, value, registry).try_into()?,
+                        )),
+                    },
                 }
             }
             _ => Err(Error::Conversion(format!(
@@ -394,11 +355,277 @@ impl TryFrom<&Cbor> for BooleanExpr {
     }
 }
 
-impl TryFrom<(&str, &Cbor)> for ComparisonExpr {
+/// Reads the operands of a n-ary `&&` or `||` expression,
+/// folded from the left into binary expressions (`a && b && c` is `(a && b) && c`)
+fn logical_expr(
+    value: &Cbor,
+    registry: &FunctionRegistry,
+    operator: fn(Box<BooleanExpr>, Box<BooleanExpr>) -> BooleanExpr,
+) -> Result<BooleanExpr, Error> {
+    let mut expr = None;
+    if let ItemKind::Array(operands) = value.kind() {
+        for operand in operands {
+            let operand = (operand, registry).try_into()?;
+            expr = Some(match expr {
+                Some(left) => operator(Box::new(left), Box::new(operand)),
+                None => operand,
+            });
+        }
+    }
+
+    expr.ok_or_else(|| {
+        Error::Conversion(format!("Cannot parse boolean expression from `{value:?}`"))
+    })
+}
+
+impl TryFrom<(&str, &Cbor, &FunctionRegistry)> for Function {
+    type Error = Error;
+
+    fn try_from(value: (&str, &Cbor, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (identifier, value, registry) = value;
+
+        match BuiltInFunction::from_identifier(identifier) {
+            Some(BuiltInFunction::Length) => {
+                Ok(Function::Length(Box::new((value, registry).try_into()?)))
+            }
+            Some(BuiltInFunction::Count) => {
+                Ok(Function::Count(path_argument(identifier, value, registry)?))
+            }
+            Some(BuiltInFunction::Value) => {
+                Ok(Function::Value(path_argument(identifier, value, registry)?))
+            }
+            Some(BuiltInFunction::Tag) => {
+                Ok(Function::Tag(Box::new((value, registry).try_into()?)))
+            }
+            Some(BuiltInFunction::Match) => {
+                let (comparable, regex) = comparable_pair(identifier, value, registry)?;
+                Ok(Function::Match(
+                    Box::new(comparable),
+                    RegexArgument::new(regex, true)?,
+                ))
+            }
+            Some(BuiltInFunction::Search) => {
+                let (comparable, regex) = comparable_pair(identifier, value, registry)?;
+                Ok(Function::Search(
+                    Box::new(comparable),
+                    RegexArgument::new(regex, false)?,
+                ))
+            }
+            Some(BuiltInFunction::Substring) => {
+                let arguments = comparable_arguments(identifier, value, registry)?;
+                let mut arguments = arguments.into_iter();
+                match (
+                    arguments.next(),
+                    arguments.next(),
+                    arguments.next(),
+                    arguments.next(),
+                ) {
+                    (Some(comparable), Some(start), end, None) => Ok(Function::Substring(
+                        Box::new(comparable),
+                        Box::new(start),
+                        end.map(Box::new),
+                    )),
+                    _ => Err(Error::Conversion(format!(
+                        "Function `{identifier}` expects 2 or 3 arguments, found `{value:?}`"
+                    ))),
+                }
+            }
+            Some(BuiltInFunction::Concat) => {
+                match comparable_arguments(identifier, value, registry)? {
+                    comparables if comparables.is_empty() => Err(Error::Conversion(format!(
+                        "Function `{identifier}` expects at least one argument"
+                    ))),
+                    comparables => Ok(Function::Concat(comparables)),
+                }
+            }
+            Some(BuiltInFunction::StringTest(test)) => {
+                let (value, pattern) = comparable_pair(identifier, value, registry)?;
+                Ok(Function::StringTest(
+                    test,
+                    Box::new(value),
+                    Box::new(pattern),
+                ))
+            }
+            Some(BuiltInFunction::CaseConversion(conversion)) => Ok(Function::CaseConversion(
+                conversion,
+                Box::new((value, registry).try_into()?),
+            )),
+            Some(BuiltInFunction::Arithmetic(operator)) => {
+                let (left, right) = comparable_pair(identifier, value, registry)?;
+                Ok(Function::Arithmetic(
+                    operator,
+                    Box::new(left),
+                    Box::new(right),
+                ))
+            }
+            Some(BuiltInFunction::Numeric(function)) => Ok(Function::Numeric(
+                function,
+                Box::new((value, registry).try_into()?),
+            )),
+            None => match registry.get(identifier) {
+                Some(definition) => Ok(Function::User(
+                    definition.clone(),
+                    user_function_arguments(identifier, definition.parameters(), value, registry)?,
+                )),
+                None => Err(Error::Conversion(format!(
+                    "Unknown function `{identifier}`"
+                ))),
+            },
+        }
+    }
+}
+
+/// Identifier of a literal value, e.g. `{"lit": ["a", "b"]}` for an array which is not a path
+const LITERAL_IDENTIFIER: &str = "lit";
+
+/// Functions known by the conversion, which cannot be shadowed by a user-defined function
+#[derive(Clone, Copy)]
+enum BuiltInFunction {
+    Length,
+    Count,
+    Value,
+    Tag,
+    Match,
+    Search,
+    Substring,
+    Concat,
+    StringTest(StringTest),
+    CaseConversion(CaseConversion),
+    Arithmetic(ArithmeticOperator),
+    Numeric(NumericFunction),
+}
+
+impl BuiltInFunction {
+    fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "length" => Some(BuiltInFunction::Length),
+            "count" => Some(BuiltInFunction::Count),
+            "value" => Some(BuiltInFunction::Value),
+            "tag" => Some(BuiltInFunction::Tag),
+            "match" => Some(BuiltInFunction::Match),
+            "search" => Some(BuiltInFunction::Search),
+            "substring" => Some(BuiltInFunction::Substring),
+            "concat" => Some(BuiltInFunction::Concat),
+            _ => StringTest::from_identifier(identifier)
+                .map(BuiltInFunction::StringTest)
+                .or_else(|| {
+                    CaseConversion::from_identifier(identifier).map(BuiltInFunction::CaseConversion)
+                })
+                .or_else(|| {
+                    ArithmeticOperator::from_identifier(identifier).map(BuiltInFunction::Arithmetic)
+                })
+                .or_else(|| {
+                    NumericFunction::from_identifier(identifier).map(BuiltInFunction::Numeric)
+                }),
+        }
+    }
+}
+
+/// Checks if `identifier` is already read by the conversion as a built-in function,
+/// a membership operator or a literal value
+pub(crate) fn is_reserved_identifier(identifier: &str) -> bool {
+    identifier == LITERAL_IDENTIFIER
+        || MembershipOperator::from_identifier(identifier).is_some()
+        || BuiltInFunction::from_identifier(identifier).is_some()
+}
+
+/// Reads the arguments of a user-defined function, according to the types of its parameters
+fn user_function_arguments(
+    identifier: &str,
+    parameters: &[FunctionType],
+    value: &Cbor,
+    registry: &FunctionRegistry,
+) -> Result<Vec<Argument>, Error> {
+    let arguments = match value.kind() {
+        ItemKind::Array(a) if value.tags().is_empty() => a.collect::<Vec<_>>(),
+        _ => {
+            return Err(Error::Conversion(format!(
+                "Function `{identifier}` expects an array of arguments, found `{value:?}`"
+            )))
+        }
+    };
+
+    if arguments.len() != parameters.len() {
+        return Err(Error::Conversion(format!(
+            "Function `{identifier}` expects {} argument(s), found {}",
+            parameters.len(),
+            arguments.len()
+        )));
+    }
+
+    parameters
+        .iter()
+        .zip(arguments)
+        .map(|(parameter, argument)| match parameter {
+            FunctionType::Value => Ok(Argument::Value((argument, registry).try_into()?)),
+            FunctionType::Logical => Ok(Argument::Logical((argument, registry).try_into()?)),
+            FunctionType::Nodes => Ok(Argument::Nodes(path_argument(
+                identifier, argument, registry,
+            )?)),
+        })
+        .collect()
+}
+
+/// Reads an argument of type `NodesType`, such as the argument of the `count` and `value` functions
+fn path_argument(
+    identifier: &str,
+    value: &Cbor,
+    registry: &FunctionRegistry,
+) -> Result<FilterPath, Error> {
+    if is_path(value) {
+        (value, registry).try_into()
+    } else {
+        Err(Error::Conversion(format!(
+            "Function `{identifier}` expects a path argument, found `{value:?}`"
+        )))
+    }
+}
+
+/// Only untagged arrays are read as paths,
+/// tagged arrays (e.g. decimal fractions) are literal values
+#[inline]
+fn is_path(value: &Cbor) -> bool {
+    matches!(value.kind(), ItemKind::Array(_)) && value.tags().is_empty()
+}
+
+/// Reads the `[<comparable>, ...]` arguments of a function
+fn comparable_arguments(
+    identifier: &str,
+    value: &Cbor,
+    registry: &FunctionRegistry,
+) -> Result<Vec<Comparable>, Error> {
+    match value.kind() {
+        ItemKind::Array(a) if value.tags().is_empty() => a
+            .map(|comparable| (comparable, registry).try_into())
+            .collect(),
+        _ => Err(Error::Conversion(format!(
+            "Function `{identifier}` expects an array of arguments, found `{value:?}`"
+        ))),
+    }
+}
+
+/// Reads the `[<comparable>, <comparable>]` arguments of the `match` and `search` functions,
+/// of the string tests and of the arithmetic operators
+fn comparable_pair(
+    identifier: &str,
+    value: &Cbor,
+    registry: &FunctionRegistry,
+) -> Result<(Comparable, Comparable), Error> {
+    if let ItemKind::Array(mut a) = value.kind() {
+        if let (Some(left), Some(right), None) = (a.next(), a.next(), a.next()) {
+            return Ok(((left, registry).try_into()?, (right, registry).try_into()?));
+        }
+    }
+    Err(Error::Conversion(format!(
+        "Cannot parse {identifier} function from `{value:?}`"
+    )))
+}
+
+impl TryFrom<(&str, &Cbor, &FunctionRegistry)> for ComparisonExpr {
     type Error = Error;
 
-    fn try_from(value: (&str, &Cbor)) -> Result<Self, Self::Error> {
-        let (identifier, value) = value;
+    fn try_from(value: (&str, &Cbor, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (identifier, value, registry) = value;
 
         let operator = match identifier {
             "<" => ComparisonOperator::Lt,
@@ -417,9 +644,9 @@ impl TryFrom<(&str, &Cbor)> for ComparisonExpr {
         if let ItemKind::Array(mut a) = value.kind() {
             match (a.next(), a.next(), a.next()) {
                 (Some(left), Some(right), None) => Ok(ComparisonExpr::new(
-                    left.try_into()?,
+                    (left, registry).try_into()?,
                     operator,
-                    right.try_into()?,
+                    (right, registry).try_into()?,
                 )),
                 _ => Err(Error::Conversion(format!(
                     "Cannot parse comparison from `{value:?}`"
@@ -433,10 +660,47 @@ impl TryFrom<(&str, &Cbor)> for ComparisonExpr {
     }
 }
 
-impl TryFrom<&Cbor> for Comparable {
+impl TryFrom<(MembershipOperator, &Cbor, &FunctionRegistry)> for MembershipExpr {
     type Error = Error;
 
-    fn try_from(value: &Cbor) -> Result<Self, Self::Error> {
+    fn try_from(
+        value: (MembershipOperator, &Cbor, &FunctionRegistry),
+    ) -> Result<Self, Self::Error> {
+        let (operator, value, registry) = value;
+
+        // as in comparisons, a literal array is wrapped in `{"lit": ..}` not to be read as a path
+        if let ItemKind::Array(mut a) = value.kind() {
+            match (a.next(), a.next(), a.next()) {
+                (Some(array), Some(value), None) if operator == MembershipOperator::Contains => {
+                    Ok(MembershipExpr::new(
+                        (value, registry).try_into()?,
+                        operator,
+                        (array, registry).try_into()?,
+                    ))
+                }
+                (Some(value), Some(array), None) => Ok(MembershipExpr::new(
+                    (value, registry).try_into()?,
+                    operator,
+                    (array, registry).try_into()?,
+                )),
+                _ => Err(Error::Conversion(format!(
+                    "Cannot parse membership from `{value:?}`"
+                ))),
+            }
+        } else {
+            Err(Error::Conversion(format!(
+                "Cannot parse membership from `{value:?}`"
+            )))
+        }
+    }
+}
+
+impl TryFrom<(&Cbor, &FunctionRegistry)> for Comparable {
+    type Error = Error;
+
+    fn try_from(value: (&Cbor, &FunctionRegistry)) -> Result<Self, Self::Error> {
+        let (value, registry) = value;
+
         match value.kind() {
             ItemKind::Pos(_)
             | ItemKind::Neg(_)
@@ -446,7 +710,17 @@ impl TryFrom<&Cbor> for Comparable {
             | ItemKind::Bool(_)
             | ItemKind::Simple(_)
             | ItemKind::Null => Ok(Comparable::Value(value.to_owned())),
-            ItemKind::Array(a) => Ok(Comparable::SingularPath(a.try_into()?)),
+            // tagged array, e.g. a decimal fraction
+            ItemKind::Array(_) if !is_path(value) => Ok(Comparable::Value(value.to_owned())),
+            ItemKind::Array(a) => match a.try_into() {
+                Ok(singular_path) => Ok(Comparable::SingularPath(singular_path)),
+                Err(e) => match FilterPath::try_from((value, registry)) {
+                    Ok(path) => Err(Error::Conversion(format!(
+                        "Non-singular path `{path}` cannot be used as a comparable"
+                    ))),
+                    Err(_) => Err(e),
+                },
+            },
             ItemKind::Dict(mut d) => {
                 let (Some((identifier, value)), None) = (d.next(), d.next()) else {
                     return Err(Error::Conversion("Expected a single element map".to_owned()));
@@ -456,16 +730,19 @@ impl TryFrom<&Cbor> for Comparable {
                     return Err(Error::Conversion("Expected a single element map".to_owned()));
                 };
 
-                match identifier.as_str() {
-                    Some("length") => Ok(Comparable::Function(Function::Length(Box::new(
-                        value.try_into()?,
-                    )))),
-                    Some("count") => Ok(Comparable::Function(Function::Count(value.try_into()?))),
-                    Some("value") => Ok(Comparable::Function(Function::Value(value.try_into()?))),
-                    _ => Err(Error::Conversion(
-                        "Expected `length`, `count` or `value` function".to_owned(),
-                    )),
+                let Some(identifier) = identifier.as_str() else {
+                    return Err(Error::Conversion("Expected a single element map".to_owned()));
+                };
+
+                // any value, e.g. an array or a map which would be read as a path or a function
+                if identifier == LITERAL_IDENTIFIER {
+                    return Ok(Comparable::Value(value.to_owned()));
                 }
+
+                // the type of the function is checked with the whole expression
+                Ok(Comparable::Function(
+                    (identifier, value, registry).try_into()?,
+                ))
             }
             _ => Err(Error::Conversion(format!(
                 "Cannot parse comparable from `{value:?}`"
@@ -539,7 +816,10 @@ impl TryFrom<&Cbor> for SingularSegment {
 
                 match identifier.as_str() {
                     Some("#") => Ok(SingularSegment::Index(value.try_into()?)),
-                    _ => Err(Error::Conversion("Expected identifier `#`".to_owned())),
+                    Some("key") => Ok(SingularSegment::Key(KeySelector::new(value.to_owned()))),
+                    _ => Err(Error::Conversion(
+                        "Expected identifier `#` or `key`".to_owned(),
+                    )),
                 }
             }
             _ => Err(Error::Conversion(format!(
@@ -548,3 +828,344 @@ impl TryFrom<&Cbor> for SingularSegment {
         }
     }
 }
+
+/// Writes an element of a CBORPath expression back to its `CBOR` form
+pub(crate) trait ToCbor {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output;
+}
+
+impl ToCbor for AbsolutePath {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        write_path("$", self.get_segments(), writer)
+    }
+}
+
+impl ToCbor for FilterPath {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        match self {
+            FilterPath::Abs(path) => write_path("$", path.get_segments(), writer),
+            FilterPath::Rel(path) => write_path("@", path.get_segments(), writer),
+        }
+    }
+}
+
+fn write_path<W: Writer>(identifier: &str, segments: &[Segment], writer: W) -> W::Output {
+    writer.write_array(None, |builder| {
+        builder.write_str(identifier, None);
+        for segment in segments {
+            segment.write_cbor(&mut *builder);
+        }
+    })
+}
+
+impl ToCbor for Segment {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        match self {
+            Segment::Child(selectors) => write_selectors(selectors, writer),
+            Segment::Descendant(selectors) => writer.write_dict(None, |builder| {
+                builder.with_key("..", |b| write_selectors(selectors, b));
+            }),
+        }
+    }
+}
+
+/// A unique selector is written with the shortcut form (without the surrounding array)
+fn write_selectors<W: Writer>(selectors: &[Selector], writer: W) -> W::Output {
+    match selectors {
+        [selector] => selector.write_cbor(writer),
+        _ => writer.write_array(None, |builder| {
+            for selector in selectors {
+                selector.write_cbor(&mut *builder);
+            }
+        }),
+    }
+}
+
+/// Array and map keys are escaped not to be read as segments or selectors
+fn write_key<W: Writer>(key: &Cbor, writer: W) -> W::Output {
+    match key.kind() {
+        ItemKind::Array(_) | ItemKind::Dict(_) => writer.write_dict(None, |builder| {
+            builder.with_key("key", |b| b.write_item(key));
+        }),
+        _ => writer.write_item(key),
+    }
+}
+
+impl ToCbor for Selector {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        match self {
+            Selector::Key(selector) => write_key(selector.get_key(), writer),
+            Selector::Wildcard => writer.write_dict(None, |builder| {
+                builder.with_key("*", |b| b.write_pos(1, None));
+            }),
+            Selector::Index(selector) => writer.write_dict(None, |builder| {
+                builder.with_key("#", |b| write_integer(selector.get_index(), b));
+            }),
+            Selector::Slice(selector) => writer.write_dict(None, |builder| {
+                let (start, end, step) = selector.get_bounds();
+                builder.with_key(":", |b| {
+                    b.write_array(None, |builder| {
+                        write_optional_integer(start, &mut *builder);
+                        write_optional_integer(end, &mut *builder);
+                        write_integer(step, &mut *builder);
+                    })
+                });
+            }),
+            Selector::Filter(selector) => writer.write_dict(None, |builder| {
+                builder.with_key("?", |b| selector.get_boolean_expr().write_cbor(b));
+            }),
+            Selector::Tag(selector) => writer.write_dict(None, |builder| {
+                builder.with_key("tag", |b| b.write_pos(selector.get_tag(), None));
+            }),
+            Selector::Embedded => writer.write_dict(None, |builder| {
+                builder.with_key("embedded", |b| b.write_pos(1, None));
+            }),
+        }
+    }
+}
+
+fn write_integer<W: Writer>(value: isize, writer: W) -> W::Output {
+    if value >= 0 {
+        writer.write_pos(value as u64, None)
+    } else {
+        writer.write_neg((-1 - value) as u64, None)
+    }
+}
+
+fn write_optional_integer<W: Writer>(value: Option<isize>, writer: W) -> W::Output {
+    match value {
+        Some(value) => write_integer(value, writer),
+        None => writer.write_null(None),
+    }
+}
+
+impl ToCbor for BooleanExpr {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        match self {
+            BooleanExpr::Or(..) => writer.write_dict(None, |builder| {
+                builder.with_key("||", |b| write_logical_operands(self, b));
+            }),
+            BooleanExpr::And(..) => writer.write_dict(None, |builder| {
+                builder.with_key("&&", |b| write_logical_operands(self, b));
+            }),
+            BooleanExpr::Not(expr) => writer.write_dict(None, |builder| {
+                builder.with_key("!", |b| expr.write_cbor(b));
+            }),
+            BooleanExpr::Comparison(comparison) => comparison.write_cbor(writer),
+            BooleanExpr::Path(path) => path.write_cbor(writer),
+            BooleanExpr::Function(function) => function.write_cbor(writer),
+            BooleanExpr::Membership(membership) => membership.write_cbor(writer),
+        }
+    }
+}
+
+/// A chain of the same logical operator is written as a single n-ary expression
+fn write_logical_operands<W: Writer>(expr: &BooleanExpr, writer: W) -> W::Output {
+    let mut operands = Vec::new();
+    let mut current = expr;
+    loop {
+        match (expr, current) {
+            (BooleanExpr::And(..), BooleanExpr::And(left, right))
+            | (BooleanExpr::Or(..), BooleanExpr::Or(left, right)) => {
+                operands.push(right.as_ref());
+                current = left;
+            }
+            _ => {
+                operands.push(current);
+                break;
+            }
+        }
+    }
+
+    writer.write_array(None, |builder| {
+        for operand in operands.iter().rev() {
+            operand.write_cbor(&mut *builder);
+        }
+    })
+}
+
+fn write_pair<L: ToCbor, R: ToCbor, W: Writer>(left: &L, right: &R, writer: W) -> W::Output {
+    writer.write_array(None, |builder| {
+        left.write_cbor(&mut *builder);
+        right.write_cbor(&mut *builder);
+    })
+}
+
+impl ToCbor for ComparisonExpr {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        let identifier = match self.get_operator() {
+            ComparisonOperator::Lt => "<",
+            ComparisonOperator::Lte => "<=",
+            ComparisonOperator::Eq => "==",
+            ComparisonOperator::Neq => "!=",
+            ComparisonOperator::Gte => ">=",
+            ComparisonOperator::Gt => ">",
+        };
+
+        writer.write_dict(None, |builder| {
+            builder.with_key(identifier, |b| {
+                write_pair(self.get_left(), self.get_right(), b)
+            });
+        })
+    }
+}
+
+impl ToCbor for MembershipExpr {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        let operator = *self.get_operator();
+
+        writer.write_dict(None, |builder| {
+            builder.with_key(operator.identifier(), |b| {
+                if operator == MembershipOperator::Contains {
+                    write_pair(self.get_array(), self.get_value(), b)
+                } else {
+                    write_pair(self.get_value(), self.get_array(), b)
+                }
+            });
+        })
+    }
+}
+
+impl ToCbor for Comparable {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        match self {
+            // untagged arrays and maps are wrapped not to be read as paths or functions
+            Comparable::Value(value)
+                if is_path(value) || matches!(value.kind(), ItemKind::Dict(_)) =>
+            {
+                writer.write_dict(None, |builder| {
+                    builder.with_key(LITERAL_IDENTIFIER, |b| b.write_item(value));
+                })
+            }
+            Comparable::Value(value) => writer.write_item(value),
+            Comparable::SingularPath(path) => path.write_cbor(writer),
+            Comparable::Function(function) => function.write_cbor(writer),
+        }
+    }
+}
+
+impl ToCbor for SingularPath {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        let (identifier, segments) = match self {
+            SingularPath::Abs(segments) => ("$", segments),
+            SingularPath::Rel(segments) => ("@", segments),
+        };
+
+        writer.write_array(None, |builder| {
+            builder.write_str(identifier, None);
+            for segment in segments {
+                match segment {
+                    SingularSegment::Key(selector) => {
+                        write_key(selector.get_key(), &mut *builder);
+                    }
+                    SingularSegment::Index(selector) => {
+                        builder.write_dict(None, |builder| {
+                            builder.with_key("#", |b| write_integer(selector.get_index(), b));
+                        });
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl ToCbor for Function {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        match self {
+            Function::Length(comparable) => writer.write_dict(None, |builder| {
+                builder.with_key("length", |b| comparable.write_cbor(b));
+            }),
+            Function::Count(path) => writer.write_dict(None, |builder| {
+                builder.with_key("count", |b| path.write_cbor(b));
+            }),
+            Function::Value(path) => writer.write_dict(None, |builder| {
+                builder.with_key("value", |b| path.write_cbor(b));
+            }),
+            Function::Tag(comparable) => writer.write_dict(None, |builder| {
+                builder.with_key("tag", |b| comparable.write_cbor(b));
+            }),
+            Function::Match(comparable, regex) => {
+                write_regex_function("match", comparable, regex, writer)
+            }
+            Function::Search(comparable, regex) => {
+                write_regex_function("search", comparable, regex, writer)
+            }
+            Function::Arithmetic(operator, left, right) => writer.write_dict(None, |builder| {
+                builder.with_key(operator.as_str(), |b| {
+                    write_pair(left.as_ref(), right.as_ref(), b)
+                });
+            }),
+            Function::Numeric(function, comparable) => writer.write_dict(None, |builder| {
+                builder.with_key(function.as_str(), |b| comparable.write_cbor(b));
+            }),
+            Function::StringTest(test, value, pattern) => writer.write_dict(None, |builder| {
+                builder.with_key(test.as_str(), |b| {
+                    write_pair(value.as_ref(), pattern.as_ref(), b)
+                });
+            }),
+            Function::CaseConversion(conversion, comparable) => {
+                writer.write_dict(None, |builder| {
+                    builder.with_key(conversion.as_str(), |b| comparable.write_cbor(b));
+                })
+            }
+            Function::Substring(comparable, start, end) => writer.write_dict(None, |builder| {
+                builder.with_key("substring", |b| {
+                    b.write_array(None, |builder| {
+                        comparable.write_cbor(&mut *builder);
+                        start.write_cbor(&mut *builder);
+                        if let Some(end) = end {
+                            end.write_cbor(&mut *builder);
+                        }
+                    })
+                });
+            }),
+            Function::Concat(comparables) => writer.write_dict(None, |builder| {
+                builder.with_key("concat", |b| {
+                    b.write_array(None, |builder| {
+                        for comparable in comparables {
+                            comparable.write_cbor(&mut *builder);
+                        }
+                    })
+                });
+            }),
+            Function::User(definition, arguments) => writer.write_dict(None, |builder| {
+                builder.with_key(definition.name(), |b| {
+                    b.write_array(None, |builder| {
+                        for argument in arguments {
+                            argument.write_cbor(&mut *builder);
+                        }
+                    })
+                });
+            }),
+        }
+    }
+}
+
+impl ToCbor for Argument {
+    fn write_cbor<W: Writer>(&self, writer: W) -> W::Output {
+        match self {
+            Argument::Value(comparable) => comparable.write_cbor(writer),
+            Argument::Logical(expr) => expr.write_cbor(writer),
+            Argument::Nodes(path) => path.write_cbor(writer),
+        }
+    }
+}
+
+fn write_regex_function<W: Writer>(
+    identifier: &str,
+    comparable: &Comparable,
+    regex: &RegexArgument,
+    writer: W,
+) -> W::Output {
+    writer.write_dict(None, |builder| {
+        builder.with_key(identifier, |b| {
+            b.write_array(None, |builder| {
+                comparable.write_cbor(&mut *builder);
+                match regex {
+                    RegexArgument::Literal(regex) => builder.write_str(regex.as_str(), None),
+                    RegexArgument::Comparable(comparable) => comparable.write_cbor(&mut *builder),
+                };
+            })
+        });
+    })
+}
@@ -0,0 +1,336 @@
+use crate::{
+    parser::is_name_first, AbsolutePath, Argument, BooleanExpr, Comparable, ComparisonExpr,
+    ComparisonOperator, FilterPath, Function, KeySelector, MembershipExpr, MembershipOperator,
+    RegexArgument, Segment, Selector, SingularPath, SingularSegment,
+};
+use cbor_data::{Cbor, ItemKind};
+use std::fmt::{self, Display, Formatter, Write};
+
+impl Display for AbsolutePath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char('$')?;
+        write_segments(self.get_segments(), f)
+    }
+}
+
+impl Display for FilterPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterPath::Abs(path) => path.fmt(f),
+            FilterPath::Rel(path) => {
+                f.write_char('@')?;
+                write_segments(path.get_segments(), f)
+            }
+        }
+    }
+}
+
+fn write_segments(segments: &[Segment], f: &mut Formatter<'_>) -> fmt::Result {
+    for segment in segments {
+        write!(f, "{segment}")?;
+    }
+    Ok(())
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Child(selectors) => match selectors.as_slice() {
+                [Selector::Key(key)] if is_member_name(key.get_key()) => {
+                    write!(f, ".{key}")
+                }
+                _ => write_bracketed_selection(selectors, f),
+            },
+            Segment::Descendant(selectors) => match selectors.as_slice() {
+                [Selector::Key(key)] if is_member_name(key.get_key()) => {
+                    write!(f, "..{key}")
+                }
+                [Selector::Wildcard] => f.write_str("..*"),
+                _ => {
+                    f.write_str("..")?;
+                    write_bracketed_selection(selectors, f)
+                }
+            },
+        }
+    }
+}
+
+fn write_bracketed_selection(selectors: &[Selector], f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_char('[')?;
+    for (i, selector) in selectors.iter().enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+        write!(f, "{selector}")?;
+    }
+    f.write_char(']')
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Selector::Key(key) => write_literal(key.get_key(), f),
+            Selector::Wildcard => f.write_char('*'),
+            Selector::Index(index) => write!(f, "{}", index.get_index()),
+            Selector::Slice(slice) => {
+                let (start, end, step) = slice.get_bounds();
+                if let Some(start) = start {
+                    write!(f, "{start}")?;
+                }
+                f.write_char(':')?;
+                if let Some(end) = end {
+                    write!(f, "{end}")?;
+                }
+                if step != 1 {
+                    write!(f, ":{step}")?;
+                }
+                Ok(())
+            }
+            Selector::Filter(filter) => write!(f, "?{}", filter.get_boolean_expr()),
+            // no JSONPath syntax: displayed as the equivalent filter selector.
+            // This is lossy: the text is parsed back as a filter selector calling the `tag`
+            // function, which selects the same nodes but is not the same expression.
+            Selector::Tag(tag) => write!(f, "?tag(@)=={}", tag.get_tag()),
+            // no JSONPath syntax: displayed as the CBOR diagnostic notation of embedded CBOR
+            Selector::Embedded => f.write_str("<<>>"),
+        }
+    }
+}
+
+/// Only used for keys which are valid member names
+impl Display for KeySelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.get_key().kind() {
+            ItemKind::Str(str) => write!(f, "{str}"),
+            _ => write!(f, "{}", self.get_key()),
+        }
+    }
+}
+
+impl Display for BooleanExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BooleanExpr::Or(left, right) => {
+                write!(f, "{left} || ")?;
+                write_operand(right, matches!(**right, BooleanExpr::Or(..)), f)
+            }
+            BooleanExpr::And(left, right) => {
+                write_operand(left, matches!(**left, BooleanExpr::Or(..)), f)?;
+                f.write_str(" && ")?;
+                write_operand(
+                    right,
+                    matches!(**right, BooleanExpr::Or(..) | BooleanExpr::And(..)),
+                    f,
+                )
+            }
+            BooleanExpr::Not(expr) => {
+                f.write_char('!')?;
+                write_operand(
+                    expr,
+                    !matches!(**expr, BooleanExpr::Path(_) | BooleanExpr::Function(_)),
+                    f,
+                )
+            }
+            BooleanExpr::Comparison(comparison) => comparison.fmt(f),
+            BooleanExpr::Path(path) => path.fmt(f),
+            BooleanExpr::Function(function) => function.fmt(f),
+            BooleanExpr::Membership(membership) => membership.fmt(f),
+        }
+    }
+}
+
+fn write_operand(expr: &BooleanExpr, parenthesized: bool, f: &mut Formatter<'_>) -> fmt::Result {
+    if parenthesized {
+        write!(f, "({expr})")
+    } else {
+        write!(f, "{expr}")
+    }
+}
+
+impl Display for ComparisonExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let operator = match self.get_operator() {
+            ComparisonOperator::Eq => "==",
+            ComparisonOperator::Neq => "!=",
+            ComparisonOperator::Gt => ">",
+            ComparisonOperator::Gte => ">=",
+            ComparisonOperator::Lt => "<",
+            ComparisonOperator::Lte => "<=",
+        };
+        write!(f, "{}{operator}{}", self.get_left(), self.get_right())
+    }
+}
+
+impl Display for MembershipExpr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.get_operator() {
+            MembershipOperator::Contains => {
+                write!(f, "{} contains {}", self.get_array(), self.get_value())
+            }
+            operator => write!(
+                f,
+                "{} {} {}",
+                self.get_value(),
+                operator.as_str(),
+                self.get_array()
+            ),
+        }
+    }
+}
+
+impl Display for Comparable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparable::Value(value) => write_literal(value, f),
+            Comparable::SingularPath(path) => path.fmt(f),
+            Comparable::Function(function) => function.fmt(f),
+        }
+    }
+}
+
+impl Display for SingularPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let segments = match self {
+            SingularPath::Abs(segments) => {
+                f.write_char('$')?;
+                segments
+            }
+            SingularPath::Rel(segments) => {
+                f.write_char('@')?;
+                segments
+            }
+        };
+
+        for segment in segments {
+            match segment {
+                SingularSegment::Key(key) if is_member_name(key.get_key()) => write!(f, ".{key}")?,
+                SingularSegment::Key(key) => {
+                    f.write_char('[')?;
+                    write_literal(key.get_key(), f)?;
+                    f.write_char(']')?;
+                }
+                SingularSegment::Index(index) => write!(f, "[{}]", index.get_index())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Function::Length(comparable) => write!(f, "length({comparable})"),
+            Function::Count(path) => write!(f, "count({path})"),
+            Function::Value(path) => write!(f, "value({path})"),
+            Function::Tag(comparable) => write!(f, "tag({comparable})"),
+            Function::Match(comparable, regex) => write!(f, "match({comparable},{regex})"),
+            Function::Search(comparable, regex) => write!(f, "search({comparable},{regex})"),
+            Function::Arithmetic(operator, left, right) => {
+                write!(f, "({left}{}{right})", operator.as_str())
+            }
+            Function::Numeric(function, comparable) => {
+                write!(f, "{}({comparable})", function.as_str())
+            }
+            Function::StringTest(test, value, pattern) => {
+                write!(f, "{}({value},{pattern})", test.as_str())
+            }
+            Function::CaseConversion(conversion, comparable) => {
+                write!(f, "{}({comparable})", conversion.as_str())
+            }
+            Function::Substring(comparable, start, None) => {
+                write!(f, "substring({comparable},{start})")
+            }
+            Function::Substring(comparable, start, Some(end)) => {
+                write!(f, "substring({comparable},{start},{end})")
+            }
+            Function::Concat(comparables) => {
+                f.write_str("concat(")?;
+                for (i, comparable) in comparables.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{comparable}")?;
+                }
+                f.write_char(')')
+            }
+            Function::User(definition, arguments) => {
+                write!(f, "{}(", definition.name())?;
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i > 0 {
+                        f.write_char(',')?;
+                    }
+                    match argument {
+                        Argument::Value(comparable) => write!(f, "{comparable}")?,
+                        Argument::Logical(expr) => write!(f, "{expr}")?,
+                        Argument::Nodes(path) => write!(f, "{path}")?,
+                    }
+                }
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+impl Display for RegexArgument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RegexArgument::Literal(regex) => write_string_literal(regex.as_str(), f),
+            RegexArgument::Comparable(comparable) => write!(f, "{comparable}"),
+        }
+    }
+}
+
+/// Writes a text string as a JSONPath string literal,
+/// or any other value in CBOR diagnostic notation
+pub(crate) fn write_literal(value: &Cbor, f: &mut Formatter<'_>) -> fmt::Result {
+    match value.kind() {
+        ItemKind::Str(str) if value.tags().is_empty() => write_string_literal(&str.as_cow(), f),
+        ItemKind::Array(elements) if value.tags().is_empty() => {
+            f.write_char('[')?;
+            for (i, element) in elements.enumerate() {
+                if i > 0 {
+                    f.write_char(',')?;
+                }
+                write_literal(element, f)?;
+            }
+            f.write_char(']')
+        }
+        _ => write!(f, "{value}"),
+    }
+}
+
+/// cf. https://www.rfc-editor.org/rfc/rfc9535.html#name-normalized-paths
+pub(crate) fn write_string_literal(str: &str, f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_char('\'')?;
+    for c in str.chars() {
+        match c {
+            '\'' => f.write_str("\\'")?,
+            '\\' => f.write_str("\\\\")?,
+            '\u{8}' => f.write_str("\\b")?,
+            '\u{c}' => f.write_str("\\f")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c < '\u{20}' => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('\'')
+}
+
+/// Checks if a key can be written with the member name shorthand
+fn is_member_name(key: &Cbor) -> bool {
+    match key.kind() {
+        ItemKind::Str(str) if key.tags().is_empty() => {
+            let str = str.as_cow();
+            let mut chars = str.chars();
+            match chars.next() {
+                Some(c) if is_name_first(c) => {
+                    chars.all(|c| is_name_first(c) || c.is_ascii_digit())
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
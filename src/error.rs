@@ -16,7 +16,12 @@ pub enum Error {
     Parsing(String),
     /// Raised if an error occurs while calling [`CborPath::write`](crate::CborPath::write) 
     /// or [`CborPath::write_from_bytes`](crate::CborPath::write_from_bytes)
-    Write(String,)
+    Write(String,),
+    /// Raised if a JSONPath text expression or a JSON Pointer is not valid.
+    /// The message indicates the column where the error occured.
+    /// # See
+    /// [`CborPath::parse`](crate::CborPath::parse), [`Path::from_json_pointer`](crate::Path::from_json_pointer)
+    Syntax(String),
 }
 
 impl From<str::Utf8Error> for Error {
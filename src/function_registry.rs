@@ -0,0 +1,237 @@
+use crate::{conversion::is_reserved_identifier, Error, FunctionType};
+use cbor_data::{Cbor, CborOwned};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+/// Argument passed to a user-defined function,
+/// evaluated according to the [`type`](FunctionType) of its parameter
+#[derive(Debug)]
+pub enum FunctionArgument<'a> {
+    /// A `CBOR` value, or [`None`] for `Nothing` (e.g. a singular path which selects no node)
+    Value(Option<Cow<'a, Cbor>>),
+    /// Result of a boolean expression
+    Logical(bool),
+    /// Nodelist selected by a path
+    Nodes(Vec<&'a Cbor>),
+}
+
+type ValueFunction = dyn Fn(&[FunctionArgument]) -> Option<CborOwned> + Send + Sync;
+type LogicalFunction = dyn Fn(&[FunctionArgument]) -> bool + Send + Sync;
+
+enum FunctionImpl {
+    Value(Box<ValueFunction>),
+    Logical(Box<LogicalFunction>),
+}
+
+/// Function registered in a [`FunctionRegistry`]
+pub(crate) struct FunctionDefinition {
+    name: String,
+    parameters: Vec<FunctionType>,
+    function: FunctionImpl,
+}
+
+impl FunctionDefinition {
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn parameters(&self) -> &[FunctionType] {
+        &self.parameters
+    }
+
+    #[inline]
+    pub fn result_type(&self) -> FunctionType {
+        match self.function {
+            FunctionImpl::Value(_) => FunctionType::Value,
+            FunctionImpl::Logical(_) => FunctionType::Logical,
+        }
+    }
+
+    /// Returns [`None`] (`Nothing`) if the function is not of type `ValueType`
+    pub fn call_as_value(&self, arguments: &[FunctionArgument]) -> Option<CborOwned> {
+        match &self.function {
+            FunctionImpl::Value(function) => function(arguments),
+            FunctionImpl::Logical(_) => None,
+        }
+    }
+
+    /// Returns `false` if the function is not of type `LogicalType`
+    pub fn call_as_logical(&self, arguments: &[FunctionArgument]) -> bool {
+        match &self.function {
+            FunctionImpl::Logical(function) => function(arguments),
+            FunctionImpl::Value(_) => false,
+        }
+    }
+}
+
+impl Debug for FunctionDefinition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FunctionDefinition")
+            .field("name", &self.name)
+            .field("parameters", &self.parameters)
+            .field("result_type", &self.result_type())
+            .finish()
+    }
+}
+
+/// Registry of user-defined functions, which can be called in filter expressions
+/// converted with [`CborPath::from_value_with`](crate::CborPath::from_value_with)
+///
+/// A function is called in the same way as the built-in functions:
+/// `{"<name>": [<argument>, ...]}`, with one argument per declared parameter.
+/// Each argument is converted and type-checked according to the [`type`](FunctionType) of its parameter:
+/// * [`FunctionType::Value`] - a [`comparable`](crate::builder::ComparableBuilder)
+/// * [`FunctionType::Logical`] - a boolean expression
+/// * [`FunctionType::Nodes`] - a path
+///
+/// # Example
+/// ```
+/// use cborpath::{CborPath, Error, FunctionArgument, FunctionRegistry, FunctionType};
+/// use cbor_data::{CborBuilder, ItemKind, Writer};
+///
+/// fn main() -> Result<(), Error> {
+///     let mut registry = FunctionRegistry::new();
+///     // true if the argument is an even integer
+///     registry.register_logical_function("even", &[FunctionType::Value], |arguments| {
+///         match &arguments[0] {
+///             FunctionArgument::Value(Some(value)) => matches!(value.kind(), ItemKind::Pos(i) if i % 2 == 0),
+///             _ => false,
+///         }
+///     })?;
+///
+///     // ["$", {"?": {"even": [["@"]]}}]
+///     let cbor_path = CborPath::from_value_with(
+///         &CborBuilder::new().write_array(None, |builder| {
+///             builder.write_str("$", None);
+///             builder.write_dict(None, |builder| {
+///                 builder.with_key("?", |b| {
+///                     b.write_dict(None, |builder| {
+///                         builder.with_key("even", |b| {
+///                             b.write_array(None, |builder| {
+///                                 builder.write_array(None, |builder| {
+///                                     builder.write_str("@", None);
+///                                 });
+///                             })
+///                         });
+///                     })
+///                 });
+///             });
+///         }),
+///         &registry,
+///     )?;
+///     assert_eq!("$[?even(@)]", cbor_path.to_string());
+///
+///     let value = CborBuilder::new().write_array(None, |builder| {
+///         for i in 1..=4 {
+///             builder.write_pos(i, None);
+///         }
+///     });
+///     let results = cbor_path.read(&value);
+///     assert_eq!(2, results.len());
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct FunctionRegistry {
+    functions: HashMap<String, Arc<FunctionDefinition>>,
+}
+
+impl FunctionRegistry {
+    /// Creates an empty registry
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a function of type `ValueType`, which can be used as a [`comparable`](crate::builder::ComparableBuilder)
+    ///
+    /// # Arguments
+    /// * `name` - name of the function, a lowercase letter followed by lowercase letters, digits or `_`
+    /// * `parameters` - types of the parameters of the function
+    /// * `function` - implementation of the function, called with one argument per parameter.
+    ///   Returning [`None`] stands for `Nothing`.
+    ///
+    /// # Errors
+    /// An [`Error::Conversion`] if `name` is not a valid function name or is the name of a built-in function.
+    #[inline]
+    pub fn register_value_function<F>(
+        &mut self,
+        name: &str,
+        parameters: &[FunctionType],
+        function: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&[FunctionArgument]) -> Option<CborOwned> + Send + Sync + 'static,
+    {
+        self.register(name, parameters, FunctionImpl::Value(Box::new(function)))
+    }
+
+    /// Registers a function of type `LogicalType`, which can be used as a boolean expression
+    ///
+    /// # Arguments
+    /// * `name` - name of the function, a lowercase letter followed by lowercase letters, digits or `_`
+    /// * `parameters` - types of the parameters of the function
+    /// * `function` - implementation of the function, called with one argument per parameter.
+    ///
+    /// # Errors
+    /// An [`Error::Conversion`] if `name` is not a valid function name or is the name of a built-in function.
+    #[inline]
+    pub fn register_logical_function<F>(
+        &mut self,
+        name: &str,
+        parameters: &[FunctionType],
+        function: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(&[FunctionArgument]) -> bool + Send + Sync + 'static,
+    {
+        self.register(name, parameters, FunctionImpl::Logical(Box::new(function)))
+    }
+
+    fn register(
+        &mut self,
+        name: &str,
+        parameters: &[FunctionType],
+        function: FunctionImpl,
+    ) -> Result<(), Error> {
+        if !is_function_name(name) {
+            return Err(Error::Conversion(format!("Invalid function name `{name}`")));
+        }
+
+        if is_reserved_identifier(name) {
+            return Err(Error::Conversion(format!(
+                "Cannot register built-in function `{name}`"
+            )));
+        }
+
+        self.functions.insert(
+            name.to_owned(),
+            Arc::new(FunctionDefinition {
+                name: name.to_owned(),
+                parameters: parameters.to_vec(),
+                function,
+            }),
+        );
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, name: &str) -> Option<&Arc<FunctionDefinition>> {
+        self.functions.get(name)
+    }
+}
+
+/// function-name = function-name-first *function-name-char
+///
+/// cf. https://www.rfc-editor.org/rfc/rfc9535.html#name-function-extensions
+fn is_function_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some('a'..='z'))
+        && chars.all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+}
@@ -0,0 +1,312 @@
+use crate::Error;
+use regex::Regex;
+use std::{iter::Peekable, str::CharIndices};
+
+/// Regular expression in the interoperable format of
+/// [I-Regexp (RFC 9485)](https://www.rfc-editor.org/rfc/rfc9485.html),
+/// translated to the syntax of the [`regex`](https://docs.rs/regex) crate
+#[derive(Debug)]
+pub(crate) struct IRegexp {
+    pattern: String,
+    regex: Regex,
+}
+
+impl IRegexp {
+    /// Compiles an I-Regexp which must match the whole input string
+    pub fn full_match(pattern: &str) -> Result<Self, Error> {
+        let translated = translate(pattern)?;
+        Self::compile(pattern, &format!("^(?:{translated})$"))
+    }
+
+    /// Compiles an I-Regexp which can match any substring of the input string
+    pub fn substring_match(pattern: &str) -> Result<Self, Error> {
+        let translated = translate(pattern)?;
+        Self::compile(pattern, &translated)
+    }
+
+    fn compile(pattern: &str, regex: &str) -> Result<Self, Error> {
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            regex: Regex::new(regex)?,
+        })
+    }
+
+    /// Returns the original I-Regexp pattern
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    #[inline]
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+impl PartialEq for IRegexp {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+    }
+}
+
+/// Validates an I-Regexp pattern and translates it to the syntax of the `regex` crate
+///
+/// cf. https://www.rfc-editor.org/rfc/rfc9485.html#name-i-regexp-syntax
+fn translate(pattern: &str) -> Result<String, Error> {
+    let mut translator = Translator {
+        pattern,
+        chars: pattern.char_indices().peekable(),
+        output: String::with_capacity(pattern.len()),
+    };
+
+    translator.i_regexp()?;
+    match translator.chars.peek() {
+        Some(&(pos, c)) => Err(translator.error(pos, &format!("unexpected `{c}`"))),
+        None => Ok(translator.output),
+    }
+}
+
+struct Translator<'a> {
+    pattern: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    output: String,
+}
+
+impl<'a> Translator<'a> {
+    /// i-regexp = branch *( "|" branch )
+    fn i_regexp(&mut self) -> Result<(), Error> {
+        self.branch()?;
+        while self.chars.next_if(|(_, c)| *c == '|').is_some() {
+            self.output.push('|');
+            self.branch()?;
+        }
+        Ok(())
+    }
+
+    /// branch = *piece
+    fn branch(&mut self) -> Result<(), Error> {
+        while !matches!(self.chars.peek(), None | Some((_, '|' | ')'))) {
+            self.piece()?;
+        }
+        Ok(())
+    }
+
+    /// piece = atom [ quantifier ]
+    fn piece(&mut self) -> Result<(), Error> {
+        self.atom()?;
+
+        match self.chars.peek() {
+            Some(&(_, c @ ('*' | '+' | '?'))) => {
+                self.chars.next();
+                self.output.push(c);
+            }
+            Some((_, '{')) => self.range_quantifier()?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// range-quantifier = "{" QuantExact [ "," [ QuantExact ] ] "}"
+    fn range_quantifier(&mut self) -> Result<(), Error> {
+        self.expect('{')?;
+        self.output.push('{');
+        self.quant_exact()?;
+        if self.chars.next_if(|(_, c)| *c == ',').is_some() {
+            self.output.push(',');
+            if !matches!(self.chars.peek(), Some((_, '}'))) {
+                self.quant_exact()?;
+            }
+        }
+        self.expect('}')?;
+        self.output.push('}');
+        Ok(())
+    }
+
+    /// QuantExact = 1*%x30-39
+    fn quant_exact(&mut self) -> Result<(), Error> {
+        let start = self.output.len();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+            self.output.push(c);
+        }
+        if self.output.len() == start {
+            Err(self.unexpected("digit"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// atom = NormalChar / charClass / ( "(" i-regexp ")" )
+    fn atom(&mut self) -> Result<(), Error> {
+        match self.chars.next() {
+            Some((_, '(')) => {
+                self.output.push_str("(?:");
+                self.i_regexp()?;
+                self.expect(')')?;
+                self.output.push(')');
+            }
+            // any character except line terminators
+            Some((_, '.')) => self.output.push_str(r"[^\n\r]"),
+            Some((_, '[')) => self.char_class_expr()?,
+            Some((_, '\\')) => {
+                let escape = self.escape(true)?;
+                self.output.push_str(&escape);
+            }
+            Some((_, c)) if is_normal_char(c) => {
+                self.output.push_str(&regex::escape(&c.to_string()))
+            }
+            Some((pos, c)) => return Err(self.error(pos, &format!("unexpected `{c}`"))),
+            None => return Err(self.unexpected("atom")),
+        }
+        Ok(())
+    }
+
+    /// charClassExpr = "[" [ "^" ] ( "-" / CCE1 ) *CCE1 [ "-" ] "]"
+    fn char_class_expr(&mut self) -> Result<(), Error> {
+        self.output.push('[');
+        if self.chars.next_if(|(_, c)| *c == '^').is_some() {
+            self.output.push('^');
+        }
+
+        if self.chars.next_if(|(_, c)| *c == '-').is_some() {
+            self.output.push_str(r"\-");
+        } else {
+            self.cce1()?;
+        }
+
+        loop {
+            match self.chars.peek() {
+                Some((_, ']')) => {
+                    self.chars.next();
+                    self.output.push(']');
+                    return Ok(());
+                }
+                Some((_, '-')) => {
+                    // a `-` is only allowed at the end of the class
+                    self.chars.next();
+                    self.output.push_str(r"\-");
+                    if !matches!(self.chars.peek(), Some((_, ']'))) {
+                        return Err(self.unexpected("`]`"));
+                    }
+                }
+                Some(_) => self.cce1()?,
+                None => return Err(self.unexpected("`]`")),
+            }
+        }
+    }
+
+    /// CCE1 = ( CCchar [ "-" CCchar ] ) / charClassEsc
+    fn cce1(&mut self) -> Result<(), Error> {
+        let mut lookahead = self.chars.clone();
+        if let (Some((_, '\\')), Some((_, 'p' | 'P'))) = (lookahead.next(), lookahead.next()) {
+            self.chars.next();
+            let escape = self.escape(true)?;
+            self.output.push_str(&escape);
+            return Ok(());
+        }
+
+        let start = self.cc_char()?;
+        self.output.push_str(&start);
+
+        let mut lookahead = self.chars.clone();
+        if let (Some((_, '-')), Some((_, c))) = (lookahead.next(), lookahead.next()) {
+            if c != ']' {
+                self.chars.next();
+                let end = self.cc_char()?;
+                self.output.push('-');
+                self.output.push_str(&end);
+            }
+        }
+        Ok(())
+    }
+
+    /// CCchar = ( %x00-2C / %x2E-5A / %x5E-D7FF / %xE000-10FFFF ) / SingleCharEsc
+    fn cc_char(&mut self) -> Result<String, Error> {
+        match self.chars.next() {
+            Some((_, '\\')) => self.escape(false),
+            Some((pos, c @ ('-' | '[' | ']'))) => {
+                Err(self.error(pos, &format!("unexpected `{c}`")))
+            }
+            Some((_, c)) => Ok(regex::escape(&c.to_string())),
+            None => Err(self.unexpected("`]`")),
+        }
+    }
+
+    /// Escape sequence, after the `\`:
+    /// `SingleCharEsc`, and if `allow_category` is `true`, `catEsc` or `complEsc`
+    fn escape(&mut self, allow_category: bool) -> Result<String, Error> {
+        match self.chars.next() {
+            Some((_, c @ ('n' | 'r' | 't'))) => Ok(format!("\\{c}")),
+            Some((
+                _,
+                c @ ('(' | ')' | '*' | '+' | '-' | '.' | '?' | '[' | '\\' | ']' | '^' | '{' | '|'
+                | '}'),
+            )) => Ok(format!("\\{c}")),
+            Some((_, c @ ('p' | 'P'))) if allow_category => {
+                self.expect('{')?;
+                let property = self.char_prop()?;
+                self.expect('}')?;
+                Ok(format!("\\{c}{{{property}}}"))
+            }
+            Some((pos, c)) => Err(self.error(pos, &format!("invalid escape `\\{c}`"))),
+            None => Err(self.unexpected("escaped character")),
+        }
+    }
+
+    /// charProp = IsCategory
+    fn char_prop(&mut self) -> Result<String, Error> {
+        let Some((pos, category)) = self.chars.next() else {
+            return Err(self.unexpected("character category"));
+        };
+
+        let subcategories = match category {
+            'L' => "lmotu",
+            'M' => "cen",
+            'N' => "dlo",
+            'P' => "cdefios",
+            'Z' => "lps",
+            'S' => "ckmo",
+            'C' => "cfno",
+            _ => return Err(self.error(pos, &format!("invalid character category `{category}`"))),
+        };
+
+        let mut property = category.to_string();
+        if let Some((_, c)) = self.chars.next_if(|(_, c)| *c != '}') {
+            if !subcategories.contains(c) {
+                return Err(self.error(pos, &format!("invalid character category `{category}{c}`")));
+            }
+            property.push(c);
+        }
+        Ok(property)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), Error> {
+        if self.chars.next_if(|(_, c)| *c == expected).is_some() {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{expected}`")))
+        }
+    }
+
+    fn unexpected(&mut self, expected: &str) -> Error {
+        match self.chars.peek() {
+            Some(&(pos, c)) => self.error(pos, &format!("expected {expected}, found `{c}`")),
+            None => self.error(self.pattern.len(), &format!("expected {expected}")),
+        }
+    }
+
+    fn error(&self, pos: usize, message: &str) -> Error {
+        let column = self.pattern[..pos].chars().count() + 1;
+        Error::Conversion(format!(
+            "invalid I-Regexp `{}`: {message} at column {column}",
+            self.pattern
+        ))
+    }
+}
+
+/// NormalChar = ( %x00-27 / "," / "-" / %x2F-3E / %x40-5A / %x5E-7A / %x7E-D7FF / %xE000-10FFFF )
+fn is_normal_char(c: char) -> bool {
+    !matches!(
+        c,
+        '(' | ')' | '*' | '+' | '.' | '?' | '[' | '\\' | ']' | '{' | '|' | '}'
+    )
+}
@@ -40,16 +40,19 @@ A selector produces a nodelist consisting of zero or more children of the input
 | `<CBOR Text>`<br>`<CBOR Bytes>`<br>`<CBOR Integer>`<br>`<CBOR Float>`<br>`<CBOR Boolean>`<br>`<CBOR Null>` | `key selector`: selects a child of a CBOR Map based on the child key |
 | `{"*": 1}`                                    | `wildcard selector`: selects all children of a node                                                                     |
 | `{"#": <index> }`                             | `index selector`: selects an indexed child of an array (from 0)                                                         |
-| `{":": [<start>, <end>, <step>]}`             | `array slice selector`: selects a subset of the elements of an array<br>(between `start` and `end` with a `step`)       |
+| `{":": [<start>, <end>, <step>]}`             | `array slice selector`: selects a subset of the elements of an array<br>(between `start` and `end` with a `step`)<br>`start` and `end` can be `null` to use their default value |
 | `{"?": <boolean-expr>}`                       | `filter selector`: selects particular children using a boolean expression                                               |
+| `{"tag": <tag>}`                              | `tag selector`: selects the children of a node whose outermost tag is `tag`                                              |
+| `{"embedded": 1}`                             | `embedded selector`: selects the CBOR item embedded in a byte string (untagged or with tag 24)                           |
+| `{"key": <CBOR value>}`                       | `key selector` for a key of any kind, e.g. an array or a map key which would otherwise be read as a segment or a selector |
 
 ### Boolean expression
 A boolean expression returns `true` or `false` and is used by a `filter selector` to filter array elements or map items.
 
 | Syntax                                        | Description                                                                                                             |
 |-----------------------------------------------|-------------------------------------------------------------------------------------------------------------------------|
-| `{"&&": [<boolean-expr>, <boolean-expr>]}`    | logical `AND`                                                                                                           |
-| `{"\|\|": [<boolean-expr>, <boolean-expr>]}`  | logical `OR`                                                                                                            |
+| `{"&&": [<boolean-expr>, <boolean-expr>, ...]}` | logical `AND` of one or more boolean expressions                                                                     |
+| `{"\|\|": [<boolean-expr>, <boolean-expr>, ...]}` | logical `OR` of one or more boolean expressions                                                                   |
 | `{"!": <boolean-expr>}`                       | logical `NOT`                                                                                                           |
 | `{"<=": [<comparable>, <comparable>]}`        | comparison `lesser than or equal                                                                                        |
 | `{"<": [<comparable>, <comparable>]}`         | comparison `lesser than`                                                                                                |
@@ -59,6 +62,16 @@ A boolean expression returns `true` or `false` and is used by a `filter selector
 | `{">=": [<comparable>, <comparable>]}`        | comparison `greater than or equal`                                                                                      |
 | `{"match": [<comparable>, <regex>]}`          | match function to compute a regular expression full match.<br>returns a boolean                                         |
 | `{"search": [<comparable>, <regex>]}`         | length function to compute a regular expression substring match.<br>returns a boolean                                   |
+| `{"starts_with": [<comparable>, <comparable>]}`<br>`{"ends_with": [<comparable>, <comparable>]}`<br>`{"contains": [<comparable>, <comparable>]}` | string functions to test if the first string begins with, ends with or contains the second string.<br>returns a boolean, `false` unless both are text strings or both are byte strings. |
+| `{"in": [<comparable>, <array>]}`<br>`{"nin": [<comparable>, <array>]}`<br>`{"array_contains": [<array>, <comparable>]}` | membership test of a value in an array, with the same equality as `==`.<br>`<array>` is a literal array of values or a singular path to an array.<br>`nin` is the negation of `in`, `array_contains` is `in` with its operands swapped |
+
+As any other array `comparable`, a literal `<array>` must be written `{"lit": <array>}` not to be read as a singular path.
+In the text syntax, these tests are written `@.category in ['fiction','poetry']`, `@.year nin $.years` and `@.tags contains 'urgent'`.
+The `contains` membership test is named `array_contains` in the `CBOR` form, where `contains` is the string function.
+
+Regular expressions (`<regex>`) must conform to the interoperable format [I-Regexp (RFC 9485)](https://www.rfc-editor.org/rfc/rfc9485.html).
+A `<regex>` is either a text string, rejected when the CBORPath expression is built if it is not a valid I-Regexp,
+or any other `comparable` (e.g. a singular path) evaluated against the document, in which case an invalid pattern never matches.
 
 ### Comparable
 A `comparable` is an operand of a `filter` comparison or an argument of a function.
@@ -66,9 +79,39 @@ A `comparable` is an operand of a `filter` comparison or an argument of a functi
 | Syntax                                        | Description                                                                                                             |
 |-----------------------------------------------|-------------------------------------------------------------------------------------------------------------------------|
 | `<CBOR Text>`<br>`<CBOR Bytes>`<br>`<CBOR Integer>`<br>`<CBOR Float>`<br>`<CBOR Boolean>`<br>`<CBOR Null>` | a `CBOR` value                                             |
+| `{"lit": <CBOR value>}`                       | a literal `CBOR` value of any kind, e.g. an array or a map which would otherwise be read as a path or a function |
 | `["$", <singular-segments>]`<br>`["@", <singular-segments>]` | a singular path (path which procudes a nodelist containing at most one node)                             |
-| `{"length": <comparable>}`                    | length function to compute the length of a value.<br>returns an unsigned integer                                        |
+| `{"length": <comparable>}`                    | length function to compute the length of a value<br>(number of characters of a text string, number of bytes of a byte string).<br>returns an unsigned integer, or nothing for a value which is not a string, an array or a map |
 | `{"count": <path>}`                           | count function to compute the number of nodes in a path.<br>returns an unsigned integer                                 |
+| `{"tag": <comparable>}`                       | tag function to get the outermost tag of a value.<br>returns an unsigned integer                                       |
+| `{"+": [<comparable>, <comparable>]}`<br>`{"-": [<comparable>, <comparable>]}`<br>`{"*": [<comparable>, <comparable>]}`<br>`{"/": [<comparable>, <comparable>]}`<br>`{"%": [<comparable>, <comparable>]}` | arithmetic expression between two integers or floats.<br>returns an integer if both operands are integers (except for a division which is not exact), a float otherwise |
+| `{"abs": <comparable>}`<br>`{"floor": <comparable>}`<br>`{"ceil": <comparable>}`<br>`{"round": <comparable>}` | absolute value and rounding functions of an integer or a float.<br>returns a number of the same kind as the argument |
+| `{"lower": <comparable>}`<br>`{"upper": <comparable>}` | case conversion of a text string.<br>returns a text string |
+| `{"substring": [<comparable>, <start>]}`<br>`{"substring": [<comparable>, <start>, <end>]}` | part of a string from the `start` index included to the `end` index excluded (negative indices count from the end).<br>returns a string of the same kind as the argument |
+| `{"concat": [<comparable>, ...]}`             | concatenation of text strings or of byte strings.<br>returns a string of the same kind as the arguments               |
+
+Function expressions must be [well-typed](https://www.rfc-editor.org/rfc/rfc9535.html#name-well-typedness-of-function-):
+`match`, `search`, `starts_with`, `ends_with` and `contains` return a boolean and are only allowed as boolean expressions,
+`length`, `count`, `value`, `tag`, `lower`, `upper`, `substring`, `concat` and arithmetic expressions return a value which must be compared,
+`count` and `value` take a path as argument and any other path used as a comparable must be a singular path.
+Ill-typed expressions are rejected with an `Error::Conversion` when the CBORPath expression is parsed or converted,
+or when it is built with `PathBuilder::try_build` (`PathBuilder::build` does not check types).
+
+`lower`, `upper`, `substring` and `concat` return nothing if an argument is not a string of the expected kind
+or if an index of `substring` is not an integer.
+Arithmetic expressions and functions return nothing if an operand is not a number, on a division by zero,
+or if the result cannot be represented as a `CBOR` number (an overflowing integer, an infinite float or NaN).
+
+User-defined functions registered in a `FunctionRegistry` are called in the same way, with `{"<name>": [<argument>, ...]}`:
+each argument is a `comparable`, a `boolean expression` or a `path` according to the type of the matching parameter.
+Such expressions are converted with `CborPath::from_value_with`.
+
+Numbers are compared by their numeric value, whatever their `CBOR` representation:
+integers, floats, bignums (tags 2 and 3) and decimal fractions (tag 4) can be compared with each other.
+
+Date/time values (tags 0 and 1) are compared by the instant they represent, and only with other date/time values:
+`0("1970-01-01T00:01:00Z")` and `1(60)` are equal.
+As an exception, an epoch-based date/time (tag 1) is compared numerically with an untagged number: `1(60)` and `60` are equal.
 
 ### Singular Segment
 A `singular segment` produces a nodelist containing at most one node.
@@ -77,6 +120,18 @@ A `singular segment` produces a nodelist containing at most one node.
 |-----------------------------------------------|-------------------------------------------------------------------------------------------------------------------------|
 | `<CBOR Text>`<br>`<CBOR Bytes>`<br>`<CBOR Integer>`<br>`<CBOR Float>`<br>`<CBOR Boolean>`<br>`<CBOR Null>` | `key selector`: selects a child of a CBOR Map based on the child key |
 | `{"#": <index> }`                             | `index selector`: selects an indexed child of an array (from 0)                                                         |
+| `{"key": <CBOR value>}`                       | `key selector` for a key of any kind                                                                                    |
+
+### JSONPath text syntax
+A CBORPath expression can also be parsed from the text syntax of [JSONPath (RFC 9535)](https://www.rfc-editor.org/rfc/rfc9535.html)
+with `CborPath::parse`. In this case, key selectors are always `CBOR Text` keys.
+
+| JSONPath text                                 | CBORPath expression                                                                                                     |
+|-----------------------------------------------|-------------------------------------------------------------------------------------------------------------------------|
+| `$.store.book[*].author`                      | `["$", "store", "book", {"*": 1}, "author"]`                                                                            |
+| `$..book[-1:]`                                | `["$", {"..": "book"}, {":": [-1, null, 1]}]`                                                                           |
+| `$..book[?@.price < 10]`                      | `["$", {"..": "book"}, {"?": {"<": [["@", "price"], 10]}}]`                                                             |
+| `$..book[?(@.price * 2) < 10]`                | `["$", {"..": "book"}, {"?": {"<": [{"*": [["@", "price"], 2]}, 10]}}]`                                                 |
 
 ## Examples
 
@@ -558,14 +613,24 @@ fn main() -> Result<(), Error> {
 ```
 */
 
+mod arithmetic;
 pub mod builder;
 mod cbor_path;
 mod conversion;
+mod display;
 mod error;
+mod function_registry;
+mod iregexp;
+mod number;
+mod parser;
+mod strings;
+mod type_check;
 mod write_visitor;
 
 pub use cbor_path::*;
 pub use error::*;
+pub use function_registry::{FunctionArgument, FunctionRegistry};
+pub use type_check::FunctionType;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,118 @@
+use cbor_data::{
+    constants::{TAG_BIGDECIMAL, TAG_BIGNUM_NEG, TAG_BIGNUM_POS},
+    value, Cbor, CborValue, ItemKind,
+};
+use num_bigint::{BigInt, BigUint};
+use num_rational::BigRational;
+use num_traits::{One, Pow};
+use std::cmp::Ordering;
+
+/// Bigger decimal exponents are not supported,
+/// to prevent huge allocations while comparing numbers
+const MAX_DECIMAL_EXPONENT: u32 = 1 << 12;
+
+/// Numeric value of a `CBOR` item, used to compare numbers of different kinds
+///
+/// cf. https://www.rfc-editor.org/rfc/rfc9535.html#name-comparisons
+#[derive(Debug, Clone)]
+pub(crate) enum Number {
+    /// Any `CBOR` integer, positive or negative
+    Int(i128),
+    Float(f64),
+    /// Bignum (tags 2 and 3) or decimal fraction (tag 4): `mantissa * 10^exponent`
+    Decimal(BigInt, i32),
+}
+
+impl Number {
+    /// Returns the numeric value of `value` or [`None`] if `value` is not a number
+    pub fn from_cbor(value: &Cbor) -> Option<Self> {
+        match value.kind() {
+            ItemKind::Pos(v) => Some(Number::Int(v.into())),
+            ItemKind::Neg(v) => Some(Number::Int(-1 - i128::from(v))),
+            ItemKind::Float(v) => Some(Number::Float(v)),
+            ItemKind::Bytes(_) | ItemKind::Array(_) => match value.tags().single() {
+                Some(TAG_BIGNUM_POS | TAG_BIGNUM_NEG | TAG_BIGDECIMAL) => Self::from_decimal(value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn from_decimal(value: &Cbor) -> Option<Self> {
+        let CborValue::Number(value::Number::Decimal(decimal)) = value.decode() else {
+            return None;
+        };
+
+        let exponent = i32::try_from(decimal.exponent()).ok()?;
+        if exponent.unsigned_abs() > MAX_DECIMAL_EXPONENT {
+            return None;
+        }
+
+        let mantissa = BigInt::from(BigUint::from_bytes_be(decimal.mantissa()));
+        let mantissa = if decimal.inverted() {
+            -BigInt::one() - mantissa
+        } else {
+            mantissa
+        };
+
+        Some(Number::Decimal(mantissa, exponent))
+    }
+
+    /// Exact value as a rational number, or [`None`] for infinite and NaN floats
+    fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Number::Int(i) => Some(BigRational::from_integer((*i).into())),
+            Number::Float(f) => BigRational::from_float(*f),
+            Number::Decimal(mantissa, exponent) => {
+                let scale = BigInt::from(10).pow(exponent.unsigned_abs());
+                if *exponent >= 0 {
+                    Some(BigRational::from_integer(mantissa * scale))
+                } else {
+                    Some(BigRational::new(mantissa.clone(), scale))
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for Number {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Int(i1), Number::Int(i2)) => Some(i1.cmp(i2)),
+            (Number::Float(f1), Number::Float(f2)) => f1.partial_cmp(f2),
+            (Number::Int(i), Number::Float(f)) => compare_int_float(*i, *f),
+            (Number::Float(f), Number::Int(i)) => compare_int_float(*i, *f).map(Ordering::reverse),
+            (Number::Float(f), _) if f.is_infinite() => Some(f.total_cmp(&0.0)),
+            (_, Number::Float(f)) if f.is_infinite() => Some(f.total_cmp(&0.0).reverse()),
+            _ => self.to_rational()?.partial_cmp(&other.to_rational()?),
+        }
+    }
+}
+
+/// Compares an integer and a float without converting the integer to a float,
+/// which would lose precision for integers greater than 2^53
+fn compare_int_float(i: i128, f: f64) -> Option<Ordering> {
+    // 2^127: `i128` bounds are [-2^127, 2^127[
+    const BOUND: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+
+    if f.is_nan() {
+        None
+    } else if f >= BOUND {
+        Some(Ordering::Less)
+    } else if f < -BOUND {
+        Some(Ordering::Greater)
+    } else {
+        let trunc = f.trunc();
+        match i.cmp(&(trunc as i128)) {
+            Ordering::Equal => 0.0.partial_cmp(&(f - trunc)),
+            ordering => Some(ordering),
+        }
+    }
+}
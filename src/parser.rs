@@ -0,0 +1,927 @@
+use crate::{
+    arithmetic::{ArithmeticOperator, NumericFunction},
+    strings::{CaseConversion, StringTest},
+    type_check::TypeCheck,
+    AbsolutePath, BooleanExpr, CborPath, Comparable, ComparisonExpr, ComparisonOperator, Error,
+    FilterPath, FilterSelector, Function, IndexSelector, KeySelector, MembershipExpr,
+    MembershipOperator, RegexArgument, RelativePath, Segment, Selector, SingularPath,
+    SingularSegment, SliceSelector,
+};
+use cbor_data::{CborBuilder, CborOwned, Literal, Writer};
+
+/// Largest integer allowed for an index or a slice bound (2^53 - 1)
+const MAX_INT: i64 = 9_007_199_254_740_991;
+
+/// Maximum nesting of filters, parentheses, function arguments and array literals,
+/// deeper expressions are rejected instead of overflowing the stack
+const MAX_DEPTH: usize = 64;
+
+/// Parses a JSONPath expression in the text syntax of
+/// [RFC 9535](https://www.rfc-editor.org/rfc/rfc9535.html) into a [`CborPath`]
+pub(crate) fn parse(input: &str) -> Result<CborPath, Error> {
+    let mut parser = Parser::new(input);
+    parser.expect('$')?;
+    let segments = parser.parse_segments()?;
+    if parser.pos < input.len() {
+        return Err(parser.unexpected());
+    }
+    // the parser only enforces the types of operands, arguments are checked with the whole path
+    segments.type_check()?;
+    Ok(CborPath::new(segments))
+}
+
+/// Operand of a filter expression, before knowing if it is used
+/// in a comparison or as a test expression
+enum Operand {
+    Literal(CborOwned),
+    Query(bool, Vec<Segment>),
+    Comparable(Comparable),
+    BooleanExpr(BooleanExpr),
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            pos: 0,
+            depth: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.input[self.pos..].chars().nth(n)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.input[self.pos..].starts_with(s)
+    }
+
+    fn consume(&mut self, s: &str) -> bool {
+        if self.starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), Error> {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected `{c}`")))
+        }
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.input[..self.pos].chars().count() + 1
+    }
+
+    fn error(&self, message: String) -> Error {
+        Error::Syntax(format!("{message} at column {}", self.column()))
+    }
+
+    fn unexpected(&self) -> Error {
+        match self.peek() {
+            Some(c) => self.error(format!("Unexpected character `{c}`")),
+            None => self.error("Unexpected end of input".to_owned()),
+        }
+    }
+
+    /// Parses segments until a character which cannot begin a segment is reached
+    fn parse_segments(&mut self) -> Result<Vec<Segment>, Error> {
+        let mut segments = Vec::new();
+
+        loop {
+            let pos = self.pos;
+            self.skip_whitespaces();
+            match self.peek() {
+                Some('[') => {
+                    segments.push(Segment::Child(self.parse_bracketed_selection()?));
+                }
+                Some('.') if self.starts_with("..") => {
+                    self.pos += 2;
+                    let selectors = match self.peek() {
+                        Some('[') => self.parse_bracketed_selection()?,
+                        Some('*') => {
+                            self.pos += 1;
+                            vec![Selector::Wildcard]
+                        }
+                        _ => vec![self.parse_member_name_shorthand()?],
+                    };
+                    segments.push(Segment::Descendant(selectors));
+                }
+                Some('.') => {
+                    self.pos += 1;
+                    let selector = match self.peek() {
+                        Some('*') => {
+                            self.pos += 1;
+                            Selector::Wildcard
+                        }
+                        _ => self.parse_member_name_shorthand()?,
+                    };
+                    segments.push(Segment::Child(vec![selector]));
+                }
+                _ => {
+                    // whitespaces are only allowed between segments
+                    self.pos = pos;
+                    return Ok(segments);
+                }
+            }
+        }
+    }
+
+    fn parse_member_name_shorthand(&mut self) -> Result<Selector, Error> {
+        let start = self.pos;
+        match self.peek() {
+            Some(c) if is_name_first(c) => {
+                self.next();
+            }
+            _ => return Err(self.error("Expected member name".to_owned())),
+        }
+        while let Some(c) = self.peek() {
+            if is_name_first(c) || c.is_ascii_digit() {
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        let name = &self.input[start..self.pos];
+        Ok(Selector::Key(KeySelector::new(
+            CborBuilder::new().write_str(name, None),
+        )))
+    }
+
+    fn parse_bracketed_selection(&mut self) -> Result<Vec<Selector>, Error> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+
+        loop {
+            self.skip_whitespaces();
+            selectors.push(self.parse_selector()?);
+            self.skip_whitespaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(selectors);
+                }
+                _ => return Err(self.error("Expected `,` or `]`".to_owned())),
+            }
+        }
+    }
+
+    fn parse_selector(&mut self) -> Result<Selector, Error> {
+        match self.peek() {
+            Some('\'' | '"') => {
+                let name = self.parse_string_literal()?;
+                Ok(Selector::Key(KeySelector::new(
+                    CborBuilder::new().write_str(&name, None),
+                )))
+            }
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                self.skip_whitespaces();
+                Ok(Selector::Filter(FilterSelector::new(
+                    self.parse_logical_or_expr()?,
+                )))
+            }
+            Some(':') => self.parse_slice_selector(None),
+            Some('-' | '0'..='9') => {
+                let index = self.parse_int()?;
+                self.skip_whitespaces();
+                if self.peek() == Some(':') {
+                    self.parse_slice_selector(Some(index))
+                } else {
+                    Ok(Selector::Index(IndexSelector::new(index)))
+                }
+            }
+            _ => Err(self.error("Expected selector".to_owned())),
+        }
+    }
+
+    /// Parses a slice selector, the optional `start` having already been parsed
+    fn parse_slice_selector(&mut self, start: Option<isize>) -> Result<Selector, Error> {
+        self.expect(':')?;
+        self.skip_whitespaces();
+
+        let end = match self.peek() {
+            Some('-' | '0'..='9') => {
+                let end = self.parse_int()?;
+                self.skip_whitespaces();
+                Some(end)
+            }
+            _ => None,
+        };
+
+        let step = if self.peek() == Some(':') {
+            self.pos += 1;
+            self.skip_whitespaces();
+            match self.peek() {
+                Some('-' | '0'..='9') => self.parse_int()?,
+                _ => 1,
+            }
+        } else {
+            1
+        };
+
+        Ok(Selector::Slice(SliceSelector::new(start, end, step)))
+    }
+
+    /// Parses an integer as defined for index selectors and slice selectors
+    fn parse_int(&mut self) -> Result<isize, Error> {
+        let start = self.pos;
+        let negative = self.consume("-");
+
+        match self.peek() {
+            Some('0') => {
+                self.pos += 1;
+                if negative {
+                    self.pos = start;
+                    return Err(self.error("Invalid integer `-0`".to_owned()));
+                }
+            }
+            Some('1'..='9') => {
+                while let Some('0'..='9') = self.peek() {
+                    self.pos += 1;
+                }
+            }
+            _ => return Err(self.error("Expected digit".to_owned())),
+        }
+
+        match self.input[start..self.pos].parse::<i64>() {
+            Ok(i) if (-MAX_INT..=MAX_INT).contains(&i) => Ok(i as isize),
+            _ => {
+                self.pos = start;
+                Err(self.error("Integer out of range".to_owned()))
+            }
+        }
+    }
+
+    fn parse_string_literal(&mut self) -> Result<String, Error> {
+        let Some(quote @ ('\'' | '"')) = self.next() else {
+            return Err(self.error("Expected string literal".to_owned()));
+        };
+
+        let mut result = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("Unterminated string literal".to_owned())),
+                Some(c) if c == quote => return Ok(result),
+                Some('\\') => {
+                    let escape_pos = self.pos - 1;
+                    match self.next() {
+                        Some('b') => result.push('\u{8}'),
+                        Some('f') => result.push('\u{c}'),
+                        Some('n') => result.push('\n'),
+                        Some('r') => result.push('\r'),
+                        Some('t') => result.push('\t'),
+                        Some('/') => result.push('/'),
+                        Some('\\') => result.push('\\'),
+                        Some(c) if c == quote => result.push(c),
+                        Some('u') => result.push(self.parse_unicode_escape()?),
+                        _ => {
+                            self.pos = escape_pos;
+                            return Err(self.error("Invalid escape sequence".to_owned()));
+                        }
+                    }
+                }
+                Some(c) if c < '\u{20}' => {
+                    self.pos -= 1;
+                    return Err(
+                        self.error("Invalid control character in string literal".to_owned())
+                    );
+                }
+                Some(c) => result.push(c),
+            }
+        }
+    }
+
+    /// Parses the hexadecimal part of a `\uXXXX` escape sequence,
+    /// including a possible low surrogate
+    fn parse_unicode_escape(&mut self) -> Result<char, Error> {
+        let high = self.parse_hex4()?;
+        match high {
+            0xD800..=0xDBFF => {
+                if !self.consume("\\u") {
+                    return Err(self.error("Expected low surrogate".to_owned()));
+                }
+                let low = self.parse_hex4()?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(self.error("Invalid low surrogate".to_owned()));
+                }
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                char::from_u32(code).ok_or_else(|| self.error("Invalid unicode escape".to_owned()))
+            }
+            0xDC00..=0xDFFF => Err(self.error("Unexpected low surrogate".to_owned())),
+            _ => {
+                char::from_u32(high).ok_or_else(|| self.error("Invalid unicode escape".to_owned()))
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, Error> {
+        let hex = self.input.get(self.pos..self.pos + 4);
+        match hex {
+            Some(hex) if hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                self.pos += 4;
+                Ok(u32::from_str_radix(hex, 16).expect("valid hexadecimal digits"))
+            }
+            _ => Err(self.error("Expected 4 hexadecimal digits".to_owned())),
+        }
+    }
+
+    fn parse_logical_or_expr(&mut self) -> Result<BooleanExpr, Error> {
+        let expr = self.parse_logical_and_expr()?;
+        self.parse_logical_or_expr_from(expr)
+    }
+
+    /// Parses the `||` operands following the already parsed left operand `expr`
+    fn parse_logical_or_expr_from(&mut self, mut expr: BooleanExpr) -> Result<BooleanExpr, Error> {
+        loop {
+            self.skip_whitespaces();
+            if !self.consume("||") {
+                return Ok(expr);
+            }
+            self.skip_whitespaces();
+            let right = self.parse_logical_and_expr()?;
+            expr = BooleanExpr::Or(Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_logical_and_expr(&mut self) -> Result<BooleanExpr, Error> {
+        let expr = self.parse_basic_expr()?;
+        self.parse_logical_and_expr_from(expr)
+    }
+
+    /// Parses the `&&` operands following the already parsed left operand `expr`
+    fn parse_logical_and_expr_from(&mut self, mut expr: BooleanExpr) -> Result<BooleanExpr, Error> {
+        loop {
+            self.skip_whitespaces();
+            if !self.consume("&&") {
+                return Ok(expr);
+            }
+            self.skip_whitespaces();
+            let right = self.parse_basic_expr()?;
+            expr = BooleanExpr::And(Box::new(expr), Box::new(right));
+        }
+    }
+
+    fn parse_basic_expr(&mut self) -> Result<BooleanExpr, Error> {
+        if self.peek() == Some('!') && self.peek_nth(1) != Some('=') {
+            self.pos += 1;
+            self.skip_whitespaces();
+            let pos = self.pos;
+            let operand = self.parse_operand()?;
+            let expr = self.test_expr_from_operand(operand, pos)?;
+            return Ok(BooleanExpr::Not(Box::new(expr)));
+        }
+
+        let left_pos = self.pos;
+        let left = self.parse_operand()?;
+        self.parse_basic_expr_from(left, left_pos)
+    }
+
+    /// Parses a comparison, a membership test or a test expression
+    /// from its already parsed left operand
+    fn parse_basic_expr_from(
+        &mut self,
+        left: Operand,
+        left_pos: usize,
+    ) -> Result<BooleanExpr, Error> {
+        let pos = self.pos;
+        self.skip_whitespaces();
+
+        let operator = if self.consume("==") {
+            ComparisonOperator::Eq
+        } else if self.consume("!=") {
+            ComparisonOperator::Neq
+        } else if self.consume("<=") {
+            ComparisonOperator::Lte
+        } else if self.consume(">=") {
+            ComparisonOperator::Gte
+        } else if self.consume("<") {
+            ComparisonOperator::Lt
+        } else if self.consume(">") {
+            ComparisonOperator::Gt
+        } else if self.consume_keyword("in") {
+            return self.parse_membership_expr(left, left_pos, MembershipOperator::In);
+        } else if self.consume_keyword("nin") {
+            return self.parse_membership_expr(left, left_pos, MembershipOperator::Nin);
+        } else if self.consume_keyword("contains") {
+            return self.parse_membership_expr(left, left_pos, MembershipOperator::Contains);
+        } else {
+            self.pos = pos;
+            return self.test_expr_from_operand(left, left_pos);
+        };
+
+        self.skip_whitespaces();
+        let right_pos = self.pos;
+        let right = self.parse_operand()?;
+
+        Ok(BooleanExpr::Comparison(ComparisonExpr::new(
+            self.comparable_from_operand(left, left_pos)?,
+            operator,
+            self.comparable_from_operand(right, right_pos)?,
+        )))
+    }
+
+    /// Parses the right operand of a membership test, after its operator:
+    /// the array of `in` and `nin`, or the value of `contains`
+    fn parse_membership_expr(
+        &mut self,
+        left: Operand,
+        left_pos: usize,
+        operator: MembershipOperator,
+    ) -> Result<BooleanExpr, Error> {
+        self.skip_whitespaces();
+        let right_pos = self.pos;
+        let right = self.parse_operand()?;
+
+        let left = self.comparable_from_operand(left, left_pos)?;
+        let right = self.comparable_from_operand(right, right_pos)?;
+        let (value, array) = match operator {
+            MembershipOperator::Contains => (right, left),
+            _ => (left, right),
+        };
+
+        Ok(BooleanExpr::Membership(MembershipExpr::new(
+            value, operator, array,
+        )))
+    }
+
+    /// Parses a parenthesized expression: either a binary arithmetic expression,
+    /// e.g. `(@.price*@.quantity)` or `((@.end-@.start)/60)`, or a logical expression,
+    /// e.g. `(@.a || @.b)`.
+    ///
+    /// The first operand is parsed once and the character following it tells both forms apart.
+    fn parse_paren_expr(&mut self) -> Result<Operand, Error> {
+        self.expect('(')?;
+        self.skip_whitespaces();
+
+        let operand = if self.peek() == Some('!') {
+            Operand::BooleanExpr(self.parse_logical_or_expr()?)
+        } else {
+            let left_pos = self.pos;
+            let left = self.parse_operand()?;
+            let pos = self.pos;
+            self.skip_whitespaces();
+
+            let operator = match self.peek() {
+                Some('+') => Some(ArithmeticOperator::Add),
+                Some('-') => Some(ArithmeticOperator::Sub),
+                Some('*') => Some(ArithmeticOperator::Mul),
+                Some('/') => Some(ArithmeticOperator::Div),
+                Some('%') => Some(ArithmeticOperator::Rem),
+                _ => None,
+            };
+
+            match operator {
+                Some(operator) => {
+                    self.pos += 1;
+                    self.parse_arithmetic_expr(left, left_pos, operator)?
+                }
+                None => {
+                    self.pos = pos;
+                    let expr = self.parse_basic_expr_from(left, left_pos)?;
+                    let expr = self.parse_logical_and_expr_from(expr)?;
+                    Operand::BooleanExpr(self.parse_logical_or_expr_from(expr)?)
+                }
+            }
+        };
+
+        self.skip_whitespaces();
+        self.expect(')')?;
+        Ok(operand)
+    }
+
+    /// Parses the right operand of a binary arithmetic expression, after its operator
+    fn parse_arithmetic_expr(
+        &mut self,
+        left: Operand,
+        left_pos: usize,
+        operator: ArithmeticOperator,
+    ) -> Result<Operand, Error> {
+        self.skip_whitespaces();
+        let right_pos = self.pos;
+        let right = self.parse_operand()?;
+
+        let left = self.comparable_from_operand(left, left_pos)?;
+        let right = self.comparable_from_operand(right, right_pos)?;
+
+        Ok(Operand::Comparable(Comparable::Function(
+            Function::Arithmetic(operator, Box::new(left), Box::new(right)),
+        )))
+    }
+
+    /// Every nested expression is parsed as an operand, so the nesting depth is checked here
+    fn parse_operand(&mut self) -> Result<Operand, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("Maximum nesting depth exceeded".to_owned()));
+        }
+        self.depth += 1;
+        let operand = self.parse_nested_operand();
+        self.depth -= 1;
+        operand
+    }
+
+    fn parse_nested_operand(&mut self) -> Result<Operand, Error> {
+        match self.peek() {
+            Some('$') => {
+                self.pos += 1;
+                Ok(Operand::Query(true, self.parse_segments()?))
+            }
+            Some('@') => {
+                self.pos += 1;
+                Ok(Operand::Query(false, self.parse_segments()?))
+            }
+            Some('\'' | '"') => {
+                let str = self.parse_string_literal()?;
+                Ok(Operand::Literal(CborBuilder::new().write_str(&str, None)))
+            }
+            Some('-' | '0'..='9') => Ok(Operand::Literal(self.parse_number()?)),
+            Some('[') => Ok(Operand::Literal(self.parse_array_literal()?)),
+            Some('(') => self.parse_paren_expr(),
+            Some('a'..='z') => {
+                if self.consume_keyword("true") {
+                    Ok(Operand::Literal(CborBuilder::new().write_bool(true, None)))
+                } else if self.consume_keyword("false") {
+                    Ok(Operand::Literal(CborBuilder::new().write_bool(false, None)))
+                } else if self.consume_keyword("null") {
+                    Ok(Operand::Literal(CborBuilder::new().write_null(None)))
+                } else {
+                    self.parse_function_expr()
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Parses an array of literal values, e.g. `['a', 1, [true, null]]`
+    fn parse_array_literal(&mut self) -> Result<CborOwned, Error> {
+        self.expect('[')?;
+        self.skip_whitespaces();
+
+        let mut elements = Vec::new();
+        if !self.consume("]") {
+            loop {
+                let pos = self.pos;
+                let element = if let Operand::Literal(value) = self.parse_operand()? {
+                    value
+                } else {
+                    self.pos = pos;
+                    return Err(self.error("Expected literal value".to_owned()));
+                };
+                elements.push(element);
+
+                self.skip_whitespaces();
+                if self.consume("]") {
+                    break;
+                }
+                self.expect(',')?;
+                self.skip_whitespaces();
+            }
+        }
+
+        Ok(CborBuilder::new().write_array(None, |builder| {
+            for element in &elements {
+                builder.write_item(element);
+            }
+        }))
+    }
+
+    /// Consumes `keyword` if it is not followed by another name character
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if !self.starts_with(keyword) {
+            return false;
+        }
+
+        match self.input[self.pos + keyword.len()..].chars().next() {
+            Some(c) if is_name_first(c) || c.is_ascii_digit() => false,
+            _ => {
+                self.pos += keyword.len();
+                true
+            }
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<CborOwned, Error> {
+        let start = self.pos;
+        self.consume("-");
+
+        match self.peek() {
+            Some('0') => self.pos += 1,
+            Some('1'..='9') => {
+                while let Some('0'..='9') = self.peek() {
+                    self.pos += 1;
+                }
+            }
+            _ => return Err(self.error("Expected digit".to_owned())),
+        }
+
+        let mut is_float = false;
+
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            if !matches!(self.peek(), Some('0'..='9')) {
+                return Err(self.error("Expected digit".to_owned()));
+            }
+            while let Some('0'..='9') = self.peek() {
+                self.pos += 1;
+            }
+            is_float = true;
+        }
+
+        if let Some('e' | 'E') = self.peek() {
+            self.pos += 1;
+            if let Some('+' | '-') = self.peek() {
+                self.pos += 1;
+            }
+            if !matches!(self.peek(), Some('0'..='9')) {
+                return Err(self.error("Expected digit".to_owned()));
+            }
+            while let Some('0'..='9') = self.peek() {
+                self.pos += 1;
+            }
+            is_float = true;
+        }
+
+        let number = &self.input[start..self.pos];
+        if !is_float {
+            // integers are kept exact in the whole CBOR range [-2^64, 2^64-1]
+            match number.parse::<i128>() {
+                Ok(i) if (0..=u64::MAX.into()).contains(&i) => {
+                    return Ok(CborBuilder::new().write_pos(i as u64, None));
+                }
+                Ok(i) if (-1 - i128::from(u64::MAX)..0).contains(&i) => {
+                    return Ok(CborBuilder::new().write_neg((-1 - i) as u64, None));
+                }
+                _ => (),
+            }
+        }
+
+        match number.parse::<f64>() {
+            Ok(f) => Ok(CborBuilder::new().write_lit(Literal::L8(f.to_bits()), None)),
+            Err(_) => {
+                self.pos = start;
+                Err(self.error("Invalid number".to_owned()))
+            }
+        }
+    }
+
+    fn parse_function_expr(&mut self) -> Result<Operand, Error> {
+        let start = self.pos;
+        while let Some('a'..='z' | '0'..='9' | '_') = self.peek() {
+            self.pos += 1;
+        }
+        let name = &self.input[start..self.pos];
+
+        if self.peek() != Some('(') {
+            self.pos = start;
+            return Err(self.unexpected());
+        }
+        self.pos += 1;
+        self.skip_whitespaces();
+
+        let operand = match name {
+            "length" => {
+                let pos = self.pos;
+                let argument = self.parse_operand()?;
+                let argument = self.comparable_from_operand(argument, pos)?;
+                Operand::Comparable(Comparable::Function(Function::Length(Box::new(argument))))
+            }
+            "tag" => {
+                let pos = self.pos;
+                let argument = self.parse_operand()?;
+                let argument = self.comparable_from_operand(argument, pos)?;
+                Operand::Comparable(Comparable::Function(Function::Tag(Box::new(argument))))
+            }
+            "count" => {
+                let path = self.parse_query_argument()?;
+                Operand::Comparable(Comparable::Function(Function::Count(path)))
+            }
+            "value" => {
+                let path = self.parse_query_argument()?;
+                Operand::Comparable(Comparable::Function(Function::Value(path)))
+            }
+            "match" | "search" => {
+                let pos = self.pos;
+                let argument = self.parse_operand()?;
+                let argument = self.comparable_from_operand(argument, pos)?;
+                self.skip_whitespaces();
+                self.expect(',')?;
+                self.skip_whitespaces();
+                let pos = self.pos;
+                let regex = self.parse_operand()?;
+                let regex = self.comparable_from_operand(regex, pos)?;
+                let regex = RegexArgument::new(regex, name == "match").map_err(|e| {
+                    self.pos = pos;
+                    self.error(e.to_string())
+                })?;
+                let function = if name == "match" {
+                    Function::Match(Box::new(argument), regex)
+                } else {
+                    Function::Search(Box::new(argument), regex)
+                };
+                Operand::BooleanExpr(BooleanExpr::Function(function))
+            }
+            "substring" => {
+                let mut arguments = self.parse_comparable_list()?.into_iter();
+                match (
+                    arguments.next(),
+                    arguments.next(),
+                    arguments.next(),
+                    arguments.next(),
+                ) {
+                    (Some(comparable), Some(from), to, None) => {
+                        Operand::Comparable(Comparable::Function(Function::Substring(
+                            Box::new(comparable),
+                            Box::new(from),
+                            to.map(Box::new),
+                        )))
+                    }
+                    _ => {
+                        self.pos = start;
+                        return Err(
+                            self.error(format!("Function `{name}` expects 2 or 3 arguments"))
+                        );
+                    }
+                }
+            }
+            "concat" => {
+                let arguments = self.parse_comparable_list()?;
+                Operand::Comparable(Comparable::Function(Function::Concat(arguments)))
+            }
+            _ => {
+                if let Some(test) = StringTest::from_identifier(name) {
+                    let [value, pattern] = self.parse_comparable_arguments(name, start)?;
+                    Operand::BooleanExpr(BooleanExpr::Function(Function::StringTest(
+                        test,
+                        Box::new(value),
+                        Box::new(pattern),
+                    )))
+                } else if let Some(conversion) = CaseConversion::from_identifier(name) {
+                    let [comparable] = self.parse_comparable_arguments(name, start)?;
+                    Operand::Comparable(Comparable::Function(Function::CaseConversion(
+                        conversion,
+                        Box::new(comparable),
+                    )))
+                } else if let Some(function) = NumericFunction::from_identifier(name) {
+                    let [comparable] = self.parse_comparable_arguments(name, start)?;
+                    Operand::Comparable(Comparable::Function(Function::Numeric(
+                        function,
+                        Box::new(comparable),
+                    )))
+                } else {
+                    self.pos = start;
+                    return Err(self.error(format!("Unknown function `{name}`")));
+                }
+            }
+        };
+
+        self.skip_whitespaces();
+        self.expect(')')?;
+        Ok(operand)
+    }
+
+    /// Parses exactly `N` comparable arguments of the function `name` beginning at `start`
+    fn parse_comparable_arguments<const N: usize>(
+        &mut self,
+        name: &str,
+        start: usize,
+    ) -> Result<[Comparable; N], Error> {
+        self.parse_comparable_list()?.try_into().map_err(|_| {
+            self.pos = start;
+            self.error(format!("Function `{name}` expects {N} argument(s)"))
+        })
+    }
+
+    /// Parses a comma-separated list of at least one comparable
+    fn parse_comparable_list(&mut self) -> Result<Vec<Comparable>, Error> {
+        let mut comparables = Vec::new();
+        loop {
+            let pos = self.pos;
+            let comparable = self.parse_operand()?;
+            comparables.push(self.comparable_from_operand(comparable, pos)?);
+            self.skip_whitespaces();
+            if self.peek() != Some(',') {
+                return Ok(comparables);
+            }
+            self.pos += 1;
+            self.skip_whitespaces();
+        }
+    }
+
+    fn parse_query_argument(&mut self) -> Result<FilterPath, Error> {
+        let pos = self.pos;
+        match self.parse_operand()? {
+            Operand::Query(is_absolute, segments) => Ok(to_filter_path(is_absolute, segments)),
+            _ => {
+                self.pos = pos;
+                Err(self.error("Expected filter query".to_owned()))
+            }
+        }
+    }
+
+    fn test_expr_from_operand(
+        &mut self,
+        operand: Operand,
+        pos: usize,
+    ) -> Result<BooleanExpr, Error> {
+        match operand {
+            Operand::Query(is_absolute, segments) => {
+                Ok(BooleanExpr::Path(to_filter_path(is_absolute, segments)))
+            }
+            Operand::BooleanExpr(expr) => Ok(expr),
+            Operand::Literal(_) | Operand::Comparable(_) => {
+                self.pos = pos;
+                Err(self.error("Expected test expression or comparison".to_owned()))
+            }
+        }
+    }
+
+    fn comparable_from_operand(
+        &mut self,
+        operand: Operand,
+        pos: usize,
+    ) -> Result<Comparable, Error> {
+        match operand {
+            Operand::Literal(value) => Ok(Comparable::Value(value)),
+            Operand::Comparable(comparable) => Ok(comparable),
+            Operand::Query(is_absolute, segments) => {
+                match to_singular_path(is_absolute, segments) {
+                    Some(path) => Ok(Comparable::SingularPath(path)),
+                    None => {
+                        self.pos = pos;
+                        Err(self.error("Expected singular query".to_owned()))
+                    }
+                }
+            }
+            Operand::BooleanExpr(_) => {
+                self.pos = pos;
+                Err(self.error("Expected comparable".to_owned()))
+            }
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn is_name_first(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c >= '\u{80}'
+}
+
+fn to_filter_path(is_absolute: bool, segments: Vec<Segment>) -> FilterPath {
+    if is_absolute {
+        FilterPath::Abs(AbsolutePath::new(segments))
+    } else {
+        FilterPath::Rel(RelativePath::new(segments))
+    }
+}
+
+fn to_singular_path(is_absolute: bool, segments: Vec<Segment>) -> Option<SingularPath> {
+    let segments = segments
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Child(mut selectors) if selectors.len() == 1 => match selectors.pop() {
+                Some(Selector::Key(selector)) => Some(SingularSegment::Key(selector)),
+                Some(Selector::Index(selector)) => Some(SingularSegment::Index(selector)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    if is_absolute {
+        Some(SingularPath::Abs(segments))
+    } else {
+        Some(SingularPath::Rel(segments))
+    }
+}
@@ -0,0 +1,178 @@
+use crate::normalize_index;
+use cbor_data::{Cbor, CborBuilder, CborOwned, ItemKind, Writer};
+use std::borrow::Cow;
+
+/// Test between two strings, both text strings or both byte strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StringTest {
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+/// Case conversion of a text string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CaseConversion {
+    Lower,
+    Upper,
+}
+
+/// Text string or byte string value, regardless of its tags
+enum StringValue<'a> {
+    Text(Cow<'a, str>),
+    Bytes(Cow<'a, [u8]>),
+}
+
+impl<'a> StringValue<'a> {
+    fn from_cbor(value: &'a Cbor) -> Option<Self> {
+        match value.kind() {
+            ItemKind::Str(s) => Some(StringValue::Text(s.as_cow())),
+            ItemKind::Bytes(b) => Some(StringValue::Bytes(b.as_cow())),
+            _ => None,
+        }
+    }
+}
+
+impl StringTest {
+    /// Returns `false` if `value` and `pattern` are not both text strings or both byte strings
+    pub fn apply(self, value: &Cbor, pattern: &Cbor) -> bool {
+        match (
+            StringValue::from_cbor(value),
+            StringValue::from_cbor(pattern),
+        ) {
+            (Some(StringValue::Text(value)), Some(StringValue::Text(pattern))) => match self {
+                StringTest::StartsWith => value.starts_with(pattern.as_ref()),
+                StringTest::EndsWith => value.ends_with(pattern.as_ref()),
+                StringTest::Contains => value.contains(pattern.as_ref()),
+            },
+            (Some(StringValue::Bytes(value)), Some(StringValue::Bytes(pattern))) => match self {
+                StringTest::StartsWith => value.starts_with(&pattern),
+                StringTest::EndsWith => value.ends_with(&pattern),
+                StringTest::Contains => {
+                    pattern.is_empty()
+                        || value.windows(pattern.len()).any(|w| w == pattern.as_ref())
+                }
+            },
+            _ => false,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StringTest::StartsWith => "starts_with",
+            StringTest::EndsWith => "ends_with",
+            StringTest::Contains => "contains",
+        }
+    }
+
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "starts_with" => Some(StringTest::StartsWith),
+            "ends_with" => Some(StringTest::EndsWith),
+            "contains" => Some(StringTest::Contains),
+            _ => None,
+        }
+    }
+}
+
+impl CaseConversion {
+    /// Returns [`None`] (`Nothing`) if `value` is not a text string
+    pub fn apply(self, value: &Cbor) -> Option<CborOwned> {
+        let ItemKind::Str(s) = value.kind() else {
+            return None;
+        };
+
+        let s = s.as_cow();
+        let converted = match self {
+            CaseConversion::Lower => s.to_lowercase(),
+            CaseConversion::Upper => s.to_uppercase(),
+        };
+        Some(CborBuilder::new().write_str(&converted, None))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CaseConversion::Lower => "lower",
+            CaseConversion::Upper => "upper",
+        }
+    }
+
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "lower" => Some(CaseConversion::Lower),
+            "upper" => Some(CaseConversion::Upper),
+            _ => None,
+        }
+    }
+}
+
+/// Part of a text string (indexed by Unicode scalar values) or of a byte string (indexed by bytes)
+/// from `start` included to `end` excluded, or to the end of the string if `end` is [`None`]
+///
+/// Negative indices count from the end of the string, and indices are clamped to the string bounds.
+/// Returns [`None`] (`Nothing`) if `value` is not a string or if an index is not an integer.
+pub(crate) fn substring(value: &Cbor, start: &Cbor, end: Option<&Cbor>) -> Option<CborOwned> {
+    let value = StringValue::from_cbor(value)?;
+    let len = match &value {
+        StringValue::Text(s) => s.chars().count(),
+        StringValue::Bytes(b) => b.len(),
+    };
+
+    let start = bound(start, len)?;
+    let end = match end {
+        Some(end) => bound(end, len)?,
+        None => len,
+    };
+    let end = end.max(start);
+
+    match value {
+        StringValue::Text(s) => {
+            let s: String = s.chars().skip(start).take(end - start).collect();
+            Some(CborBuilder::new().write_str(&s, None))
+        }
+        StringValue::Bytes(b) => Some(CborBuilder::new().write_bytes(&b[start..end], None)),
+    }
+}
+
+/// Index clamped to `[0, len]`
+fn bound(index: &Cbor, len: usize) -> Option<usize> {
+    let index = match index.kind() {
+        ItemKind::Pos(i) => isize::try_from(i).unwrap_or(isize::MAX),
+        ItemKind::Neg(i) => isize::try_from(i).map_or(isize::MIN, |i| -1 - i),
+        _ => return None,
+    };
+    Some(normalize_index(index, len).clamp(0, len as isize) as usize)
+}
+
+/// Concatenation of text strings or of byte strings
+///
+/// Returns [`None`] (`Nothing`) if the values are not all text strings or all byte strings.
+pub(crate) fn concat(values: &[&Cbor]) -> Option<CborOwned> {
+    let values = values
+        .iter()
+        .map(|value| StringValue::from_cbor(value))
+        .collect::<Option<Vec<_>>>()?;
+
+    match values.first()? {
+        StringValue::Text(_) => {
+            let mut result = String::new();
+            for value in values {
+                let StringValue::Text(s) = value else {
+                    return None;
+                };
+                result.push_str(&s);
+            }
+            Some(CborBuilder::new().write_str(&result, None))
+        }
+        StringValue::Bytes(_) => {
+            let mut result = Vec::new();
+            for value in values {
+                let StringValue::Bytes(b) = value else {
+                    return None;
+                };
+                result.extend_from_slice(&b);
+            }
+            Some(CborBuilder::new().write_bytes(&result, None))
+        }
+    }
+}
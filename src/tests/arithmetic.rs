@@ -0,0 +1,258 @@
+use crate::{
+    arithmetic::{ArithmeticOperator, NumericFunction},
+    builder::{
+        abs, add, ceil, div, eq, floor, gt, lt, mul, or, rel_path, rem, round, sing_rel_path, sub,
+        val,
+    },
+    tests::util::{cbor_to_diag, diag_to_bytes, diag_to_cbor},
+    CborPath, Error,
+};
+
+fn apply(operator: ArithmeticOperator, left: &str, right: &str) -> Option<String> {
+    operator
+        .apply(&diag_to_cbor(left), &diag_to_cbor(right))
+        .map(|result| cbor_to_diag(&result))
+}
+
+fn apply_function(function: NumericFunction, value: &str) -> Option<String> {
+    function
+        .apply(&diag_to_cbor(value))
+        .map(|result| cbor_to_diag(&result))
+}
+
+#[test]
+fn integer_operations() {
+    use ArithmeticOperator::*;
+
+    assert_eq!(Some("5".to_owned()), apply(Add, "2", "3"));
+    assert_eq!(Some("-1".to_owned()), apply(Sub, "2", "3"));
+    assert_eq!(Some("6".to_owned()), apply(Mul, "2", "3"));
+    assert_eq!(Some("-2".to_owned()), apply(Div, "-6", "3"));
+    assert_eq!(Some("-1".to_owned()), apply(Rem, "-7", "3"));
+    // a division which is not exact returns a float
+    assert_eq!(Some("3.5_3".to_owned()), apply(Div, "7", "2"));
+    // tags are ignored
+    assert_eq!(Some("60_0".to_owned()), apply(Sub, "1(120)", "1(60)"));
+}
+
+#[test]
+fn float_operations() {
+    use ArithmeticOperator::*;
+
+    assert_eq!(Some("3.5_3".to_owned()), apply(Add, "1.5", "2"));
+    assert_eq!(Some("-0.5_3".to_owned()), apply(Sub, "1.5", "2.0"));
+    assert_eq!(Some("3.0_3".to_owned()), apply(Mul, "2", "1.5"));
+    assert_eq!(Some("0.75_3".to_owned()), apply(Div, "1.5", "2"));
+    assert_eq!(Some("1.5_3".to_owned()), apply(Rem, "7.5", "2"));
+}
+
+#[test]
+fn nothing() {
+    use ArithmeticOperator::*;
+
+    // operands which are not numbers
+    assert_eq!(None, apply(Add, r#""1""#, "2"));
+    assert_eq!(None, apply(Mul, "2", "null"));
+    // division by zero
+    assert_eq!(None, apply(Div, "1", "0"));
+    assert_eq!(None, apply(Rem, "1", "0"));
+    assert_eq!(None, apply(Div, "1.5", "0"));
+    assert_eq!(None, apply(Rem, "1", "-0.0"));
+    // out of the range of CBOR integers
+    assert_eq!(None, apply(Add, "18446744073709551615", "1"));
+    assert_eq!(None, apply(Sub, "-18446744073709551616", "1"));
+    assert_eq!(
+        None,
+        apply(Mul, "18446744073709551615", "18446744073709551615")
+    );
+    assert_eq!(
+        Some("-18446744073709551616_3".to_owned()),
+        apply(Sub, "-18446744073709551615", "1")
+    );
+    // infinite float
+    assert_eq!(None, apply(Mul, "1.0e300", "1.0e300"));
+    assert_eq!(None, apply(Add, "Infinity", "1"));
+    assert_eq!(None, apply(Add, "NaN", "1"));
+}
+
+#[test]
+fn numeric_functions() {
+    use NumericFunction::*;
+
+    assert_eq!(Some("3".to_owned()), apply_function(Abs, "-3"));
+    assert_eq!(Some("2.5_3".to_owned()), apply_function(Abs, "-2.5"));
+    assert_eq!(None, apply_function(Abs, "-18446744073709551616"));
+    assert_eq!(Some("-3".to_owned()), apply_function(Floor, "-3"));
+    assert_eq!(Some("-3.0_3".to_owned()), apply_function(Floor, "-2.5"));
+    assert_eq!(Some("-2.0_3".to_owned()), apply_function(Ceil, "-2.5"));
+    assert_eq!(Some("-3.0_3".to_owned()), apply_function(Round, "-2.5"));
+    assert_eq!(Some("2.0_3".to_owned()), apply_function(Round, "2.4"));
+    assert_eq!(None, apply_function(Round, r#""2.4""#));
+}
+
+#[test]
+fn read() -> Result<(), Error> {
+    let value = diag_to_bytes(
+        r#"[
+        {"price": 12, "quantity": 10, "start": 1(100), "end": 1(130)},
+        {"price": 2.5, "quantity": 4, "start": 1(100), "end": 1(200)},
+        {"price": 8, "quantity": 0}
+    ]"#,
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(gt(
+            mul(
+                sing_rel_path().key("price"),
+                sing_rel_path().key("quantity"),
+            ),
+            val(100),
+        ))
+        .key("price")
+        .build();
+    assert_eq!(diag_to_bytes("[12]"), cbor_path.read_from_bytes(&value)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(lt(
+            sub(sing_rel_path().key("end"), sing_rel_path().key("start")),
+            val(60),
+        ))
+        .key("price")
+        .build();
+    assert_eq!(diag_to_bytes("[12]"), cbor_path.read_from_bytes(&value)?);
+
+    // division by zero returns nothing, which is not equal to a number
+    let cbor_path = CborPath::builder()
+        .filter(eq(div(val(10), sing_rel_path().key("quantity")), val(1)))
+        .key("price")
+        .build();
+    assert_eq!(diag_to_bytes("[12]"), cbor_path.read_from_bytes(&value)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            round(add(sing_rel_path().key("price"), val(0.5))),
+            ceil(abs(rem(val(-7), val(4)))),
+        ))
+        .key("price")
+        .build();
+    assert_eq!(diag_to_bytes("[2.5]"), cbor_path.read_from_bytes(&value)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(floor(sing_rel_path().key("price")), val(2)))
+        .key("price")
+        .build();
+    assert_eq!(diag_to_bytes("[2.5]"), cbor_path.read_from_bytes(&value)?);
+
+    Ok(())
+}
+
+#[test]
+fn conversion() -> Result<(), Error> {
+    let cbor_path = CborPath::from_value(&diag_to_cbor(
+        r#"["$", {"?": {">": [{"*": [["@", "price"], {"abs": ["@", "quantity"]}]}, 100]}}]"#,
+    ))?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(gt(
+                mul(
+                    sing_rel_path().key("price"),
+                    abs(sing_rel_path().key("quantity"))
+                ),
+                val(100),
+            ))
+            .build(),
+        cbor_path
+    );
+    assert_eq!("$[?(@.price*abs(@.quantity))>100]", cbor_path.to_string());
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+    assert_eq!(cbor_path, CborPath::parse(&cbor_path.to_string())?);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            add(sub(val(1), val(2.5)), div(val(3), rem(val(4), val(5)))),
+            floor(ceil(round(val(-1)))),
+        ))
+        .build();
+    assert_eq!(
+        "$[?((1-2.5)+(3/(4%5)))==floor(ceil(round(-1)))]",
+        cbor_path.to_string()
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+    assert_eq!(cbor_path, CborPath::parse(&cbor_path.to_string())?);
+
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"+": [["@", "a"], 1]}}]"#)),
+        Err(Error::Conversion(e)) if e == "Function `(@.a+1)` of type ValueType cannot be used as a test expression, it must be compared"
+    ));
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"==": [{"+": [1]}, 1]}}]"#)),
+        Err(Error::Conversion(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn parse() -> Result<(), Error> {
+    assert_eq!(
+        CborPath::builder()
+            .filter(
+                gt(
+                    mul(
+                        sing_rel_path().key("price"),
+                        sing_rel_path().key("quantity")
+                    ),
+                    val(100)
+                ) & or(rel_path().key("a"), rel_path().key("b"))
+            )
+            .filter(lt(
+                val(-1),
+                rem(
+                    sub(sing_rel_path().key("end"), sing_rel_path().key("start")),
+                    abs(val(-60))
+                )
+            ))
+            .build(),
+        CborPath::parse(
+            "$[?(@.price * @.quantity) > 100 && (@.a || @.b)][?-1 < ((@.end-@.start) % abs(-60))]"
+        )?
+    );
+
+    // arithmetic expressions are always parenthesized and must be compared
+    assert!(matches!(
+        CborPath::parse("$[?@.a+1==2]"),
+        Err(Error::Syntax(_))
+    ));
+    assert!(matches!(
+        CborPath::parse("$[?(@.a+1)]"),
+        Err(Error::Syntax(e)) if e == "Expected test expression or comparison at column 4"
+    ));
+    assert!(matches!(
+        CborPath::parse("$[?(@.a+1+2)==2]"),
+        Err(Error::Syntax(_))
+    ));
+
+    // each parenthesized operand is parsed once, whatever the nesting
+    assert_eq!(
+        CborPath::builder().filter(rel_path().key("a")).build(),
+        CborPath::parse(&format!("$[?{}@.a{}]", "(".repeat(40), ")".repeat(40)))?
+    );
+    assert!(matches!(
+        CborPath::parse(&format!("$[?{}@.a{}==1]", "(".repeat(40), ")".repeat(40))),
+        Err(Error::Syntax(_))
+    ));
+    let mut sum = sing_rel_path().key("a").into();
+    for _ in 0..40 {
+        sum = add(sum, val(1));
+    }
+    assert_eq!(
+        CborPath::builder().filter(eq(sum, val(41))).build(),
+        CborPath::parse(&format!(
+            "$[?{}@.a{}==41]",
+            "(".repeat(40),
+            "+1)".repeat(40)
+        ))?
+    );
+
+    Ok(())
+}
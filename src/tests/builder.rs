@@ -1,6 +1,7 @@
 use crate::{
-    builder::{eq, gte, rel_path, segment, sing_rel_path, val, value},
-    BooleanExpr, CborPath, Comparable, ComparisonExpr, ComparisonOperator, FilterPath,
+    builder::{decimal, eq, gte, rel_path, segment, sing_rel_path, val, value},
+    tests::util::{diag_to_bytes, diag_to_cbor},
+    BooleanExpr, CborPath, Comparable, ComparisonExpr, ComparisonOperator, Error, FilterPath,
     FilterSelector, Function, KeySelector, RelativePath, Segment, Selector, SingularPath,
     SingularSegment,
 };
@@ -58,3 +59,53 @@ fn value_function() {
         cbor_path
     );
 }
+
+#[test]
+fn big_numbers() -> Result<(), Error> {
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path(), decimal(314, -2)))
+        .filter(eq(sing_rel_path(), decimal(-314, 0)))
+        .filter(eq(sing_rel_path(), val(18446744073709551616u128)))
+        .filter(eq(sing_rel_path(), val(-18446744073709551617i128)))
+        .filter(eq(sing_rel_path(), val(-18446744073709551616i128)))
+        .build();
+
+    assert_eq!(
+        CborPath::from_value(&diag_to_cbor(
+            r#"["$",
+            {"?": {"==": [["@"], 4([-2, 314])]}},
+            {"?": {"==": [["@"], -314]}},
+            {"?": {"==": [["@"], 2(h'010000000000000000')]}},
+            {"?": {"==": [["@"], 3(h'010000000000000000')]}},
+            {"?": {"==": [["@"], -18446744073709551616]}}]"#
+        ))?,
+        cbor_path
+    );
+
+    Ok(())
+}
+
+#[test]
+fn open_slices() -> Result<(), Error> {
+    let cbor_path = CborPath::builder().slice_bounds(None, None, -1).build();
+    assert_eq!(CborPath::parse("$[::-1]")?, cbor_path);
+    assert_eq!(
+        diag_to_bytes("[3, 2, 1]"),
+        cbor_path.read_from_bytes(&diag_to_bytes("[1, 2, 3]"))?
+    );
+
+    let cbor_path = CborPath::builder()
+        .child(
+            segment()
+                .slice_bounds(Some(2), None, 1)
+                .slice_bounds(None, Some(1), 1),
+        )
+        .build();
+    assert_eq!(CborPath::parse("$[2:,:1]")?, cbor_path);
+    assert_eq!(
+        diag_to_bytes("[3, 4, 1]"),
+        cbor_path.read_from_bytes(&diag_to_bytes("[1, 2, 3, 4]"))?
+    );
+
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use super::util::{diag_to_bytes, diag_to_cbor};
 use crate::{
     builder::{
-        abs_path, count, eq, gt, gte, length, lt, lte, neq, rel_path, segment, sing_abs_path,
-        sing_rel_path, val, value,
+        _match, abs_path, and, count, eq, gt, gte, length, lt, lte, neq, not, or, rel_path, search,
+        segment, sing_abs_path, sing_rel_path, tag, val, value, BooleanExprBuilder,
     },
     CborPath, Error,
 };
+use std::collections::BTreeMap;
 
 fn from_value(cbor_diag_str: &str) -> Result<CborPath, Error> {
     let value = diag_to_cbor(cbor_diag_str);
@@ -109,6 +110,9 @@ fn cbor_path_from_value() -> Result<(), Error> {
         cbor_path,
     );
 
+    let cbor_path: CborPath = from_value(r#"["$", {":": [null, -1, 1]}, {":": [1, null, -1]}]"#)?;
+    assert_eq!(CborPath::parse("$[:-1][1::-1]")?, cbor_path);
+
     let cbor_path: CborPath = from_value(r#"["$",{"..":"a"}]"#)?;
     assert_eq!(
         CborPath::builder().descendant(segment().key("a")).build(),
@@ -220,3 +224,350 @@ fn cbor_path_from_bytes() -> Result<(), Error> {
 
     Ok(())
 }
+
+fn round_trip(cbor_path: CborPath) -> Result<(), Error> {
+    let bytes = cbor_path.to_bytes();
+    assert_eq!(cbor_path, CborPath::from_bytes(&bytes)?);
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+    Ok(())
+}
+
+#[test]
+fn cbor_path_to_cbor() -> Result<(), Error> {
+    assert_eq!(diag_to_bytes(r#"["$"]"#), CborPath::root().to_bytes());
+
+    assert_eq!(
+        diag_to_bytes(r##"["$", "a", {"#": -1}, ["b", {"*": 1}], {"..": "c"}]"##),
+        CborPath::builder()
+            .key("a")
+            .index(-1)
+            .child(segment().key("b").wildcard())
+            .descendant(segment().key("c"))
+            .build()
+            .to_bytes()
+    );
+
+    assert_eq!(
+        diag_to_bytes(r##"["$", {"?": {"<": [["@", "price"], 10]}}]"##),
+        CborPath::builder()
+            .filter(lt(sing_rel_path().key("price"), val(10)))
+            .build()
+            .to_bytes()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn n_ary_logical_operators() -> Result<(), Error> {
+    let a = || BooleanExprBuilder::from(rel_path().key("a"));
+    let b = || rel_path().key("b");
+    let c = || rel_path().key("c");
+
+    let cbor_path = from_value(r#"["$", {"?": {"&&": [["@", "a"], ["@", "b"], ["@", "c"]]}}]"#)?;
+    assert_eq!(
+        CborPath::builder().filter(a() & b() & c()).build(),
+        cbor_path
+    );
+    assert_eq!(
+        CborPath::builder()
+            .filter(and(and(rel_path().key("a"), b()), c()))
+            .build(),
+        cbor_path
+    );
+    assert_eq!("$[?@.a && @.b && @.c]", cbor_path.to_string());
+
+    let cbor_path = from_value(r#"["$", {"?": {"||": [["@", "a"]]}}]"#)?;
+    assert_eq!(CborPath::builder().filter(a()).build(), cbor_path);
+
+    assert!(matches!(
+        from_value(r#"["$", {"?": {"||": []}}]"#),
+        Err(Error::Conversion(_))
+    ));
+
+    // chains of the same operator are written as a single n-ary expression
+    assert_eq!(
+        diag_to_bytes(
+            r#"["$", {"?": {"||": [{"&&": [["@", "a"], ["@", "b"], ["@", "c"]]}, {"!": ["@", "a"]}]}}]"#
+        ),
+        CborPath::builder()
+            .filter(a() & b() & c() | !a())
+            .build()
+            .to_bytes()
+    );
+    assert_eq!(
+        diag_to_bytes(r#"["$", {"?": {"&&": [["@", "a"], {"&&": [["@", "b"], ["@", "c"]]}]}}]"#),
+        CborPath::builder()
+            .filter(a() & (BooleanExprBuilder::from(b()) & c()))
+            .build()
+            .to_bytes()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cbor_path_round_trip() -> Result<(), Error> {
+    round_trip(CborPath::root())?;
+
+    round_trip(
+        CborPath::builder()
+            .key("foo")
+            .key(12)
+            .key(-12)
+            .key(12.12)
+            .key(true)
+            .key(())
+            .key("binary".as_bytes())
+            .wildcard()
+            .index(1)
+            .index(-2)
+            .slice(0, -1, 1)
+            .slice(-1, 0, -2)
+            .child(segment().key("a").index(0).wildcard())
+            .descendant(segment().key("a"))
+            .descendant(segment().key("a").slice(1, 3, 1))
+            .build(),
+    )?;
+
+    round_trip(
+        CborPath::builder()
+            .filter(abs_path().key("a"))
+            .filter(rel_path().descendant(segment().wildcard()))
+            .filter(lt(val(12), val(13)))
+            .filter(lte(val(12), val(13)))
+            .filter(neq(val(12), sing_abs_path().index(1)))
+            .filter(eq(sing_abs_path().key("a"), sing_rel_path().key("b")))
+            .filter(gte(val(12), val(13)))
+            .filter(gt(val("a"), val(())))
+            .build(),
+    )?;
+
+    round_trip(
+        CborPath::builder()
+            .filter(and(
+                or(rel_path().key("a"), not(rel_path().key("b"))),
+                _match(sing_rel_path().key("c"), "[jk]+")?,
+            ))
+            .filter(search(value(rel_path().key("d")), "^a|b$")?)
+            .filter(gte(length(sing_rel_path().key("authors")), val(5)))
+            .filter(gte(count(rel_path().wildcard().key("authors")), val(5)))
+            .filter(eq(value(abs_path().descendant(segment().key("color"))), val("red")))
+            .build(),
+    )?;
+
+    round_trip(CborPath::parse(
+        "$.values[?match(@, $.regex)][?search(@.a, value(@..b))]",
+    )?)?;
+
+    round_trip(
+        CborPath::builder()
+            .tag(32)
+            .descendant(segment().tag(1).key("a"))
+            .filter(eq(tag(sing_rel_path().key("b")), val(0)))
+            .build(),
+    )?;
+
+    round_trip(
+        CborPath::builder()
+            .key("payload")
+            .embedded()
+            .descendant(segment().embedded().key("sub"))
+            .build(),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn composite_literals() -> Result<(), Error> {
+    let value = diag_to_bytes(
+        r#"[
+        {"dims": [1, 2, 3], "meta": {"a": 1}},
+        {"dims": [1, 2], "meta": {"a": 2}},
+        {"dims": ["@", "a"]}
+    ]"#,
+    );
+
+    let cbor_path =
+        from_value(r#"["$", {"?": {"==": [["@", "dims"], {"lit": [1, 2, 3]}]}}, "meta"]"#)?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(eq(sing_rel_path().key("dims"), val(vec![1, 2, 3])))
+            .key("meta")
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        diag_to_bytes(r#"[{"a": 1}]"#),
+        cbor_path.read_from_bytes(&value)?
+    );
+    assert_eq!("$[?@.dims==[1,2,3]].meta", cbor_path.to_string());
+    assert_eq!(
+        diag_to_cbor(r#"["$", {"?": {"==": [["@", "dims"], {"lit": [1, 2, 3]}]}}, "meta"]"#),
+        cbor_path.to_cbor()
+    );
+    assert_eq!(cbor_path, CborPath::parse(&cbor_path.to_string())?);
+
+    let cbor_path =
+        from_value(r#"["$", {"?": {"==": [["@", "meta"], {"lit": {"a": 2}}]}}, "dims"]"#)?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(eq(
+                sing_rel_path().key("meta"),
+                val(BTreeMap::from([("a", 2)]))
+            ))
+            .key("dims")
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        diag_to_bytes("[[1, 2]]"),
+        cbor_path.read_from_bytes(&value)?
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    // an array which would be read as a path
+    let cbor_path =
+        from_value(r#"["$", {"?": {"==": [["@", "dims"], {"lit": ["@", "a"]}]}}, "dims"]"#)?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(eq(sing_rel_path().key("dims"), val(["@", "a"])))
+            .key("dims")
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        diag_to_bytes(r#"[["@", "a"]]"#),
+        cbor_path.read_from_bytes(&value)?
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    // simple values can also be wrapped
+    assert_eq!(
+        from_value(r#"["$", {"?": {"==": [["@", "a"], 1]}}]"#)?,
+        from_value(r#"["$", {"?": {"==": [["@", "a"], {"lit": 1}]}}]"#)?
+    );
+
+    // tagged arrays are literal values, never paths
+    let cbor_path = from_value(r#"["$", {"?": {"==": [["@", "dims"], 1000(["@", "a"])]}}]"#)?;
+    assert_eq!(
+        diag_to_cbor(r#"["$", {"?": {"==": [["@", "dims"], 1000(["@", "a"])]}}]"#),
+        cbor_path.to_cbor()
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    Ok(())
+}
+
+#[test]
+fn composite_keys() -> Result<(), Error> {
+    let value = diag_to_bytes(r#"{[1, 2]: "array", {"*": 1}: "map", "a": "text"}"#);
+
+    let cbor_path = from_value(r#"["$", {"key": [1, 2]}]"#)?;
+    assert_eq!(CborPath::builder().key(vec![1, 2]).build(), cbor_path);
+    assert_eq!(
+        diag_to_bytes(r#"["array"]"#),
+        cbor_path.read_from_bytes(&value)?
+    );
+    assert_eq!(
+        diag_to_cbor(r#"["$", {"key": [1, 2]}]"#),
+        cbor_path.to_cbor()
+    );
+
+    // a map key which would be read as a wildcard selector
+    let cbor_path = from_value(r#"["$", {"key": {"*": 1}}]"#)?;
+    assert_eq!(
+        CborPath::builder()
+            .key(&*diag_to_cbor(r#"{"*": 1}"#))
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        diag_to_bytes(r#"["map"]"#),
+        cbor_path.read_from_bytes(&value)?
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    // simple keys can also be escaped
+    let cbor_path = from_value(r#"["$", [{"key": "a"}, {"key": [1, 2]}]]"#)?;
+    assert_eq!(
+        CborPath::builder()
+            .child(segment().key("a").key(vec![1, 2]))
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        diag_to_bytes(r#"["text", "array"]"#),
+        cbor_path.read_from_bytes(&value)?
+    );
+    assert_eq!(
+        diag_to_cbor(r#"["$", ["a", {"key": [1, 2]}]]"#),
+        cbor_path.to_cbor()
+    );
+
+    // singular paths
+    let cbor_path = from_value(r#"["$", {"?": {"==": [["@", {"key": [1, 2]}], "array"]}}]"#)?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(eq(sing_rel_path().key(vec![1, 2]), val("array")))
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        diag_to_bytes(r#"[{[1, 2]: "array", {"*": 1}: "map", "a": "text"}]"#),
+        CborPath::builder()
+            .filter(eq(sing_rel_path().key(vec![1, 2]), val("array")))
+            .build()
+            .read_from_bytes(&diag_to_bytes(
+                r#"[{[1, 2]: "array", {"*": 1}: "map", "a": "text"}, {[1, 2]: "other"}]"#
+            ))?
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    Ok(())
+}
+
+#[test]
+fn type_check_errors() {
+    fn error(cbor_diag_str: &str) -> String {
+        match from_value(cbor_diag_str) {
+            Err(Error::Conversion(e)) => e,
+            result => panic!("unexpected result for `{cbor_diag_str}`: {result:?}"),
+        }
+    }
+
+    assert_eq!(
+        "Non-singular path `@[*]` cannot be used as a comparable",
+        error(r#"["$", {"?": {"==": [["@", {"*": 1}], 1]}}]"#)
+    );
+    assert_eq!(
+        "Non-singular path `@..a` cannot be used as a comparable",
+        error(r#"["$", {"?": {"==": [{"length": ["@", {"..": "a"}]}, 1]}}]"#)
+    );
+    assert_eq!(
+        "Function `count` expects a path argument, found `Cbor(01)`",
+        error(r#"["$", {"?": {"==": [{"count": 1}, 1]}}]"#)
+    );
+    assert_eq!(
+        "Function `count` expects a path argument, found `Cbor(d903e881 6140)`",
+        error(r#"["$", {"?": {"==": [{"count": 1000(["@"])}, 1]}}]"#)
+    );
+    assert_eq!(
+        "Function `length(@.a)` of type ValueType cannot be used as a test expression, it must be compared",
+        error(r#"["$", {"?": {"length": ["@", "a"]}}]"#)
+    );
+    assert_eq!(
+        "Function `match(@,'a')` of type LogicalType cannot be used as a comparable",
+        error(r#"["$", {"?": {"==": [{"match": [["@"], "a"]}, true]}}]"#)
+    );
+    // nested filter
+    assert_eq!(
+        "Function `value(@.b)` of type ValueType cannot be used as a test expression, it must be compared",
+        error(r#"["$", {"?": ["@", "a", {"?": {"value": ["@", "b"]}}]}]"#)
+    );
+    assert_eq!(
+        "Unknown function `foo`",
+        error(r#"["$", {"?": {"foo": ["@", "a"]}}]"#)
+    );
+}
@@ -0,0 +1,290 @@
+//! Runner for the [JSONPath Compliance Test Suite](https://github.com/jsonpath-standard/jsonpath-compliance-test-suite)
+//!
+//! `cts.json` is written in the format of the upstream `cts.json` file
+//! (`{"tests": [{"name", "selector", "document", "result" | "results" | "invalid_selector"}]}`).
+//! It is not the full upstream file: it holds a selection of the upstream cases
+//! and of cases written after RFC 9535, and can be replaced by a pinned release of the suite.
+//! JSON documents are converted to CBOR before evaluation, so results are compared as CBOR items.
+//!
+//! Cases that deliberately diverge from the suite are listed in [`SKIPPED`] with the reason.
+//! Any other failure fails the runner, and so does a skipped case that passes.
+use crate::CborPath;
+use cbor_data::{Cbor, CborBuilder, CborOwned, ItemKind, Literal, Writer};
+use std::{iter::Peekable, str::Chars};
+
+const CTS: &str = include_str!("cts.json");
+
+/// Cases expected to fail, as `(name, reason)`
+const SKIPPED: &[(&str, &str)] = &[
+    (
+        "functions, match, word escape is not supported",
+        INVALID_REGEX_LITERAL,
+    ),
+    (
+        "functions, match, digit escape is not supported",
+        INVALID_REGEX_LITERAL,
+    ),
+    (
+        "functions, match, backreference is not supported",
+        INVALID_REGEX_LITERAL,
+    ),
+    (
+        "functions, match, lazy quantifier is not supported",
+        INVALID_REGEX_LITERAL,
+    ),
+    (
+        "functions, match, inline flags are not supported",
+        INVALID_REGEX_LITERAL,
+    ),
+    (
+        "functions, match, non-capturing group is not supported",
+        INVALID_REGEX_LITERAL,
+    ),
+];
+
+/// Regular expression literals are compiled when the path is parsed, so an invalid
+/// I-Regexp literal is a syntax error instead of a `match` evaluating to `LogicalFalse`
+const INVALID_REGEX_LITERAL: &str = "invalid I-Regexp literals are rejected by the parser";
+
+#[test]
+fn compliance_test_suite() {
+    let cts = json_to_cbor(CTS);
+    let tests = field(&cts, "tests").expect("tests");
+
+    let mut failures = Vec::new();
+    let mut skipped = Vec::new();
+    let mut count = 0;
+    for test in array(tests) {
+        count += 1;
+        let name = text(field(test, "name").expect("name"));
+        let result = run_test(test);
+        if SKIPPED.iter().any(|(n, _)| *n == name) {
+            skipped.push(name.clone());
+            if result.is_ok() {
+                failures.push(format!("{name}: skipped but passes"));
+            }
+        } else if let Err(e) = result {
+            failures.push(format!("{name}: {e}"));
+        }
+    }
+
+    for (name, _) in SKIPPED {
+        if !skipped.iter().any(|n| n == name) {
+            failures.push(format!("{name}: skipped but not in the suite"));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}/{count} failed tests:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+fn run_test(test: &Cbor) -> Result<(), String> {
+    let selector = text(field(test, "selector").expect("selector"));
+    let cbor_path = CborPath::parse(&selector);
+
+    if field(test, "invalid_selector").is_some() {
+        return match cbor_path {
+            Ok(cbor_path) => Err(format!(
+                "`{selector}` should be invalid, parsed as {cbor_path}"
+            )),
+            Err(_) => Ok(()),
+        };
+    }
+
+    let cbor_path = cbor_path.map_err(|e| format!("cannot parse `{selector}`: {e}"))?;
+    let document = field(test, "document").expect("document");
+    let actual = cbor_path.read(document);
+
+    let expected = match field(test, "result") {
+        Some(result) => vec![result],
+        None => array(field(test, "results").expect("result or results")),
+    };
+
+    if expected.iter().any(|result| {
+        let result = array(result);
+        result.len() == actual.len() && result.iter().zip(&actual).all(|(r, a)| r == a)
+    }) {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{selector}` returned [{}]",
+            actual
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+fn field<'a>(cbor: &'a Cbor, name: &str) -> Option<&'a Cbor> {
+    match cbor.kind() {
+        ItemKind::Dict(mut entries) => entries
+            .find(|(k, _)| matches!(k.kind(), ItemKind::Str(s) if s.as_cow() == name))
+            .map(|(_, v)| v),
+        _ => panic!("expected a map, found {cbor}"),
+    }
+}
+
+fn array(cbor: &Cbor) -> Vec<&Cbor> {
+    match cbor.kind() {
+        ItemKind::Array(items) => items.collect(),
+        _ => panic!("expected an array, found {cbor}"),
+    }
+}
+
+fn text(cbor: &Cbor) -> String {
+    match cbor.kind() {
+        ItemKind::Str(s) => s.as_cow().into_owned(),
+        _ => panic!("expected a text string, found {cbor}"),
+    }
+}
+
+/// Minimal JSON parser for the suite: integers are encoded as CBOR integers
+/// and all other numbers as double precision floats
+fn json_to_cbor(json: &str) -> CborOwned {
+    let mut parser = JsonParser {
+        chars: json.chars().peekable(),
+    };
+    let cbor = parser.value(CborBuilder::new());
+    parser.skip_whitespaces();
+    assert!(parser.chars.next().is_none(), "trailing characters");
+    cbor
+}
+
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> JsonParser<'a> {
+    fn value<W: Writer>(&mut self, writer: W) -> W::Output {
+        self.skip_whitespaces();
+        match self.chars.peek() {
+            Some('{') => {
+                self.chars.next();
+                writer.write_dict(None, |builder| {
+                    self.skip_whitespaces();
+                    if self.chars.next_if_eq(&'}').is_some() {
+                        return;
+                    }
+                    loop {
+                        self.skip_whitespaces();
+                        let key = self.string();
+                        self.skip_whitespaces();
+                        self.expect(':');
+                        builder.with_key(&key, |b| self.value(b));
+                        self.skip_whitespaces();
+                        if self.chars.next_if_eq(&'}').is_some() {
+                            return;
+                        }
+                        self.expect(',');
+                    }
+                })
+            }
+            Some('[') => {
+                self.chars.next();
+                writer.write_array(None, |builder| {
+                    self.skip_whitespaces();
+                    if self.chars.next_if_eq(&']').is_some() {
+                        return;
+                    }
+                    loop {
+                        self.value(&mut *builder);
+                        self.skip_whitespaces();
+                        if self.chars.next_if_eq(&']').is_some() {
+                            return;
+                        }
+                        self.expect(',');
+                    }
+                })
+            }
+            Some('"') => {
+                let str = self.string();
+                writer.write_str(&str, None)
+            }
+            Some('t') => {
+                self.keyword("true");
+                writer.write_bool(true, None)
+            }
+            Some('f') => {
+                self.keyword("false");
+                writer.write_bool(false, None)
+            }
+            Some('n') => {
+                self.keyword("null");
+                writer.write_null(None)
+            }
+            _ => {
+                let mut number = String::new();
+                while let Some(c) = self
+                    .chars
+                    .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                {
+                    number.push(c);
+                }
+                match number.parse::<i64>() {
+                    Ok(i) if i >= 0 => writer.write_pos(i as u64, None),
+                    Ok(i) => writer.write_neg((-1 - i) as u64, None),
+                    Err(_) => {
+                        let f: f64 = number
+                            .parse()
+                            .unwrap_or_else(|_| panic!("invalid number `{number}`"));
+                        writer.write_lit(Literal::L8(f.to_bits()), None)
+                    }
+                }
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        self.expect('"');
+        let mut str = String::new();
+        loop {
+            match self.chars.next().expect("unterminated string") {
+                '"' => return str,
+                '\\' => match self.chars.next().expect("unterminated escape") {
+                    'b' => str.push('\u{8}'),
+                    'f' => str.push('\u{c}'),
+                    'n' => str.push('\n'),
+                    'r' => str.push('\r'),
+                    't' => str.push('\t'),
+                    'u' => {
+                        let mut code = self.hex4();
+                        if (0xD800..0xDC00).contains(&code) {
+                            self.expect('\\');
+                            self.expect('u');
+                            code = 0x10000 + ((code - 0xD800) << 10) + (self.hex4() - 0xDC00);
+                        }
+                        str.push(char::from_u32(code).expect("invalid code point"));
+                    }
+                    c => str.push(c),
+                },
+                c => str.push(c),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> u32 {
+        let hex: String = (0..4).filter_map(|_| self.chars.next()).collect();
+        u32::from_str_radix(&hex, 16).unwrap_or_else(|_| panic!("invalid escape `\\u{hex}`"))
+    }
+
+    fn keyword(&mut self, keyword: &str) {
+        for c in keyword.chars() {
+            self.expect(c);
+        }
+    }
+
+    fn expect(&mut self, expected: char) {
+        let c = self.chars.next();
+        assert_eq!(Some(expected), c, "unexpected character");
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.chars.next_if(|c| c.is_ascii_whitespace()).is_some() {}
+    }
+}
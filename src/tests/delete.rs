@@ -1,6 +1,7 @@
 use crate::{
-    tests::util::{cbor_to_diag, diag_to_cbor, log_try_init},
-    CborPath,
+    builder::segment,
+    tests::util::{cbor_to_diag, diag_to_bytes, diag_to_cbor, log_try_init},
+    CborPath, Error,
 };
 
 #[test]
@@ -66,3 +67,45 @@ fn array_as_value() {
 
     assert_eq!(r#"{"foo":{"c":2}}"#, cbor_to_diag(&result));
 }
+
+#[test]
+fn tagged_containers() {
+    let cbor = diag_to_cbor(r#"55799([259({"a": 1, "b": 2}), 40(1(["c", "d"]))])"#);
+
+    let cbor_path = CborPath::builder()
+        .descendant(segment().key("b").index(0))
+        .build();
+    let result = cbor_path.delete(&cbor).unwrap();
+
+    assert_eq!(
+        diag_to_cbor(r#"55799([259({"a": 1}), 40(1(["d"]))])"#),
+        result
+    );
+}
+
+#[test]
+fn embedded() -> Result<(), Error> {
+    let cbor = diag_to_bytes(r#"[<<{"a": 1, "b": <<[2, 3]>>}>>, 4]"#);
+
+    let cbor_path = CborPath::builder()
+        .index(0)
+        .embedded()
+        .key("b")
+        .embedded()
+        .index(0)
+        .build();
+    let result = cbor_path.delete_from_byte(&cbor)?;
+
+    assert_eq!(
+        Some(diag_to_bytes(r#"[<<{"a": 1, "b": <<[3]>>}>>, 4]"#)),
+        result
+    );
+
+    // deleting the embedded item deletes the byte string
+    let cbor_path = CborPath::builder().index(0).embedded().build();
+    let result = cbor_path.delete_from_byte(&cbor)?;
+
+    assert_eq!(Some(diag_to_bytes(r#"[4]"#)), result);
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use crate::{
+    builder::{
+        _match, abs_path, and, count, eq, gte, length, lt, not, or, rel_path, search, segment,
+        sing_abs_path, sing_rel_path, tag, val, value,
+    },
+    tests::util::diag_to_bytes,
+    CborPath, Error,
+};
+
+#[test]
+fn display() -> Result<(), Error> {
+    assert_eq!("$", CborPath::root().to_string());
+
+    assert_eq!(
+        "$.store.book[*].author",
+        CborPath::builder()
+            .key("store")
+            .key("book")
+            .wildcard()
+            .key("author")
+            .build()
+            .to_string()
+    );
+
+    assert_eq!(
+        "$..book[?@.price<10]",
+        CborPath::builder()
+            .descendant(segment().key("book"))
+            .filter(lt(sing_rel_path().key("price"), val(10)))
+            .build()
+            .to_string()
+    );
+
+    assert_eq!(
+        r#"$['a b']['it\'s']['\n']..*..['x',0][1:3][0:-1:2]"#,
+        CborPath::builder()
+            .key("a b")
+            .key("it's")
+            .key("\n")
+            .descendant(segment().wildcard())
+            .descendant(segment().key("x").index(0))
+            .slice(1, 3, 1)
+            .child(segment().slice(0, -1, 2))
+            .build()
+            .to_string()
+    );
+
+    assert_eq!(
+        "$[12][-1.5][h'0102'][true][null]",
+        CborPath::builder()
+            .child(segment().key(12))
+            .key(-1.5)
+            .key([1u8, 2].as_slice())
+            .key(true)
+            .key(())
+            .build()
+            .to_string()
+    );
+
+    assert_eq!(
+        "$[?(@.a || @.b) && !(@.c==1) || !$.d][?length(@.e)>=2 && count(@[*])<3]",
+        CborPath::builder()
+            .filter(or(
+                and(
+                    or(rel_path().key("a"), rel_path().key("b")),
+                    not(eq(sing_rel_path().key("c"), val(1)))
+                ),
+                not(abs_path().key("d"))
+            ))
+            .filter(and(
+                gte(length(sing_rel_path().key("e")), val(2)),
+                lt(count(rel_path().wildcard()), val(3))
+            ))
+            .build()
+            .to_string()
+    );
+
+    assert_eq!(
+        "$[?match(@['a b'][0],'[jk]+') || search(value($..c),'\\\\p{Nd}')][?$.x=='it\\'s']",
+        CborPath::builder()
+            .filter(or(
+                _match(sing_rel_path().key("a b").index(0), "[jk]+")?,
+                search(value(abs_path().descendant(segment().key("c"))), r"\p{Nd}")?
+            ))
+            .filter(eq(sing_abs_path().key("x"), val("it's")))
+            .build()
+            .to_string()
+    );
+
+    assert_eq!(
+        "$[?match(@,$.regex) && !search(@.a,value(@..b))]",
+        CborPath::parse("$[?match(@, $.regex) && !search(@.a, value(@..b))]")?.to_string()
+    );
+
+    assert_eq!(
+        "$[?tag(@)==32]..[?tag(@)==1,'a'][?tag(@.b)==0]",
+        CborPath::builder()
+            .tag(32)
+            .descendant(segment().tag(1).key("a"))
+            .filter(eq(tag(sing_rel_path().key("b")), val(0)))
+            .build()
+            .to_string()
+    );
+
+    assert_eq!(
+        "$.payload[<<>>].sub",
+        CborPath::builder()
+            .key("payload")
+            .embedded()
+            .key("sub")
+            .build()
+            .to_string()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn tag_selector_display_is_lossy() -> Result<(), Error> {
+    let cbor_path = CborPath::builder().tag(32).build();
+    let parsed = CborPath::parse(&cbor_path.to_string())?;
+
+    // parsed back as a filter selector, not as a tag selector
+    assert_ne!(cbor_path, parsed);
+    assert_eq!(
+        CborPath::builder()
+            .filter(eq(tag(sing_rel_path()), val(32)))
+            .build(),
+        parsed
+    );
+
+    // which selects the same nodes
+    let value = diag_to_bytes(r#"[32("a"), 1(2), 32(1(3)), 1(32(4)), 5]"#);
+    assert_eq!(
+        cbor_path.read_from_bytes(&value)?,
+        parsed.read_from_bytes(&value)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn display_round_trip() -> Result<(), Error> {
+    for jsonpath in [
+        "$",
+        "$.store.book[*].author",
+        "$..book[-1:]",
+        "$..book[0,1]",
+        "$[:][1:][:-1:2][::-1]",
+        "$['a\\'b'][?@.price<10.5 && @.category=='fiction']",
+        "$[?(@.a || @.b) && !(@.c==1) || !$.d]",
+        "$[?@.a || (@.b || @.c)][?@.a && (@.b && @.c)][?!(!@.a)]",
+        "$[?match(@.a,'a|b') && length(@.b)>=count(@.c[*])][?value(@..d)!=null]",
+        "$[?tag(@)==32][?tag(@.a)>=0]",
+        "$[?(@.a+1)>=abs(@.b)][?((@.c*2)%-3)==round(@.d)]",
+    ] {
+        let cbor_path = CborPath::parse(jsonpath)?;
+        assert_eq!(jsonpath, cbor_path.to_string());
+        assert_eq!(cbor_path, CborPath::parse(&cbor_path.to_string())?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use crate::{
+    tests::util::{diag_to_bytes, diag_to_cbor},
+    CborPath, Error, FunctionArgument, FunctionRegistry, FunctionType,
+};
+use cbor_data::{CborBuilder, ItemKind, Writer};
+
+fn registry() -> Result<FunctionRegistry, Error> {
+    let mut registry = FunctionRegistry::new();
+
+    // true if the value is an even integer
+    registry.register_logical_function("even", &[FunctionType::Value], |arguments| {
+        matches!(&arguments[0], FunctionArgument::Value(Some(value)) if matches!(value.kind(), ItemKind::Pos(i) if i % 2 == 0))
+    })?;
+
+    // sum of two integers
+    registry.register_value_function(
+        "add",
+        &[FunctionType::Value, FunctionType::Value],
+        |arguments| match (&arguments[0], &arguments[1]) {
+            (FunctionArgument::Value(Some(v1)), FunctionArgument::Value(Some(v2))) => {
+                match (v1.kind(), v2.kind()) {
+                    (ItemKind::Pos(i1), ItemKind::Pos(i2)) => {
+                        Some(CborBuilder::new().write_pos(i1.checked_add(i2)?, None))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+    )?;
+
+    // true if the path selects at least two nodes or if the logical argument is true
+    registry.register_logical_function(
+        "several_or",
+        &[FunctionType::Nodes, FunctionType::Logical],
+        |arguments| match (&arguments[0], &arguments[1]) {
+            (FunctionArgument::Nodes(nodes), FunctionArgument::Logical(logical)) => {
+                nodes.len() >= 2 || *logical
+            }
+            _ => false,
+        },
+    )?;
+
+    Ok(registry)
+}
+
+fn read(cbor_path: &str, value: &str) -> Result<Vec<u8>, Error> {
+    let cbor_path = CborPath::from_value_with(&diag_to_cbor(cbor_path), &registry()?)?;
+    cbor_path.read_from_bytes(&diag_to_bytes(value))
+}
+
+#[test]
+fn logical_function() -> Result<(), Error> {
+    assert_eq!(
+        diag_to_bytes("[2, 4]"),
+        read(r#"["$", {"?": {"even": [["@"]]}}]"#, r#"[1, 2, 3, 4, "a"]"#)?
+    );
+    assert_eq!(
+        diag_to_bytes("[1, 3]"),
+        read(
+            r#"["$", {"?": {"!": {"even": [["@"]]}}}]"#,
+            r#"[1, 2, 3, 4]"#
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn value_function() -> Result<(), Error> {
+    assert_eq!(
+        diag_to_bytes(r#"[{"a": 1, "b": 2}]"#),
+        read(
+            r#"["$", {"?": {"==": [{"add": [["@", "a"], ["@", "b"]]}, 3]}}]"#,
+            r#"[{"a": 1, "b": 2}, {"a": 2, "b": 2}, {"a": 1}]"#
+        )?
+    );
+    // user-defined functions can be used as arguments of other functions
+    assert_eq!(
+        diag_to_bytes(r#"[{"a": 1, "b": 1}]"#),
+        read(
+            r#"["$", {"?": {"even": [{"add": [["@", "a"], ["@", "b"]]}]}}]"#,
+            r#"[{"a": 1, "b": 1}, {"a": 2, "b": 1}]"#
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn nodes_and_logical_arguments() -> Result<(), Error> {
+    assert_eq!(
+        diag_to_bytes(r#"[[1, 2], [3]]"#),
+        read(
+            r##"["$", {"?": {"several_or": [["@", {"*": 1}], {"==": [["@", {"#": 0}], 3]}]}}]"##,
+            r#"[[1, 2], [3], [4]]"#
+        )?
+    );
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<(), Error> {
+    let registry = registry()?;
+
+    let cbor_path = CborPath::from_value_with(
+        &diag_to_cbor(
+            r#"["$", {"?": {"several_or": [["@", {"*": 1}], {"even": [{"add": [["@", "a"], 1]}]}]}}]"#,
+        ),
+        &registry,
+    )?;
+    assert_eq!(
+        "$[?several_or(@[*],even(add(@.a,1)))]",
+        cbor_path.to_string()
+    );
+    assert_eq!(
+        cbor_path,
+        CborPath::from_value_with(&cbor_path.to_cbor(), &registry)?
+    );
+    Ok(())
+}
+
+#[test]
+fn errors() -> Result<(), Error> {
+    fn error(cbor_diag_str: &str) -> String {
+        match registry().and_then(|r| CborPath::from_value_with(&diag_to_cbor(cbor_diag_str), &r)) {
+            Err(Error::Conversion(e)) => e,
+            result => panic!("unexpected result for `{cbor_diag_str}`: {result:?}"),
+        }
+    }
+
+    // functions are unknown without the registry
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"even": [["@"]]}}]"#)),
+        Err(Error::Conversion(e)) if e == "Unknown function `even`"
+    ));
+
+    assert_eq!(
+        "Function `even` expects 1 argument(s), found 2",
+        error(r#"["$", {"?": {"even": [["@"], 1]}}]"#)
+    );
+    assert_eq!(
+        "Function `even` expects an array of arguments, found `Cbor(01)`",
+        error(r#"["$", {"?": {"even": 1}}]"#)
+    );
+    assert_eq!(
+        "Function `add(@.a,1)` of type ValueType cannot be used as a test expression, it must be compared",
+        error(r#"["$", {"?": {"add": [["@", "a"], 1]}}]"#)
+    );
+    assert_eq!(
+        "Function `even(@)` of type LogicalType cannot be used as a comparable",
+        error(r#"["$", {"?": {"==": [{"even": [["@"]]}, true]}}]"#)
+    );
+    assert_eq!(
+        "Function `several_or` expects a path argument, found `Cbor(01)`",
+        error(r#"["$", {"?": {"several_or": [1, ["@"]]}}]"#)
+    );
+
+    let mut registry = FunctionRegistry::new();
+    assert!(matches!(
+        registry.register_logical_function("match", &[], |_| true),
+        Err(Error::Conversion(e)) if e == "Cannot register built-in function `match`"
+    ));
+    for name in ["length", "contains", "lower", "abs", "in", "nin", "lit"] {
+        assert!(matches!(
+            registry.register_logical_function(name, &[], |_| true),
+            Err(Error::Conversion(e)) if e == format!("Cannot register built-in function `{name}`")
+        ));
+    }
+    assert!(matches!(
+        registry.register_logical_function("Even", &[], |_| true),
+        Err(Error::Conversion(e)) if e == "Invalid function name `Even`"
+    ));
+    Ok(())
+}
@@ -6,6 +6,7 @@ use crate::{
     tests::util::diag_to_bytes,
     CborPath, Error, Path,
 };
+use cbor_data::Cbor;
 
 #[test]
 fn root() -> Result<(), Error> {
@@ -846,3 +847,41 @@ fn store() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn read_with_paths() -> Result<(), Error> {
+    let value = diag_to_bytes(r#"{"o": {"j": 1, "k": 2}, "a": [5, 3, [{"j": 4}, {"k": 6}]]}"#);
+
+    let cbor_path = CborPath::builder().descendant(segment().key("j")).build();
+    let result = cbor_path.read_with_paths_from_bytes(&value)?;
+    assert_eq!(
+        vec![
+            (
+                Path::default().key("o").key("j"),
+                diag_to_bytes("1").as_slice()
+            ),
+            (
+                Path::default().key("a").idx(2).idx(0).key("j"),
+                diag_to_bytes("4").as_slice()
+            ),
+        ],
+        result
+    );
+
+    let cbor = Cbor::checked(&value)?;
+    let cbor_path = CborPath::parse("$..*")?;
+    let result = cbor_path.read_with_paths(cbor);
+    let (paths, values): (Vec<_>, Vec<_>) = result.into_iter().unzip();
+    assert_eq!(cbor_path.get_paths(cbor), paths);
+    assert_eq!(cbor_path.read(cbor), values);
+
+    let cbor_path = CborPath::root();
+    let result = cbor_path.read_with_paths(cbor);
+    assert_eq!(vec![(Path::default(), cbor)], result);
+
+    let cbor_path = CborPath::builder().key("b").build();
+    let result = cbor_path.read_with_paths(cbor);
+    assert!(result.is_empty());
+
+    Ok(())
+}
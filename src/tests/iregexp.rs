@@ -0,0 +1,128 @@
+use crate::{
+    builder::{_match, search, sing_rel_path},
+    tests::util::diag_to_bytes,
+    CborPath, Error,
+};
+
+fn read_match(regex: &str, values: &str) -> Result<Vec<u8>, Error> {
+    let cbor_path = CborPath::builder()
+        .filter(_match(sing_rel_path(), regex)?)
+        .build();
+    cbor_path.read_from_bytes(&diag_to_bytes(values))
+}
+
+fn read_search(regex: &str, values: &str) -> Result<Vec<u8>, Error> {
+    let cbor_path = CborPath::builder()
+        .filter(search(sing_rel_path(), regex)?)
+        .build();
+    cbor_path.read_from_bytes(&diag_to_bytes(values))
+}
+
+#[test]
+fn full_match() -> Result<(), Error> {
+    // the alternation is anchored as a whole
+    let result = read_match("a|b", r#"["a", "b", "ab", "ba", "xb"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["a", "b"]"#), result);
+
+    let result = read_match("(ab)+c?", r#"["ab", "ababc", "abc ", "c"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["ab", "ababc"]"#), result);
+
+    let result = read_match("a{2}b{1,}c{0,1}", r#"["aab", "aabbbc", "ab", "aabcc"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["aab", "aabbbc"]"#), result);
+
+    // `.` does not match line terminators
+    let result = read_match("a.c", "[\"abc\", \"a\nc\", \"a\rc\", \"a\u{e9}c\"]")?;
+    assert_eq!(diag_to_bytes("[\"abc\", \"a\u{e9}c\"]"), result);
+
+    // `^` and `$` are ordinary characters
+    let result = read_match("^a$", r#"["a", "^a$"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["^a$"]"#), result);
+
+    Ok(())
+}
+
+#[test]
+fn substring_match() -> Result<(), Error> {
+    let result = read_search("a|b", r#"["a", "xbx", "c"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["a", "xbx"]"#), result);
+
+    let result = read_search(r"\p{Lu}\p{Nd}", r#"["xA1x", "a1", "A"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["xA1x"]"#), result);
+
+    let result = read_search(r"\P{L}", r#"["abc", "ab1"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["ab1"]"#), result);
+
+    Ok(())
+}
+
+#[test]
+fn char_class() -> Result<(), Error> {
+    let result = read_match("[a-c]+", r#"["abc", "abd", "-"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["abc"]"#), result);
+
+    let result = read_match("[^a-c]", r#"["a", "d", "-"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["d", "-"]"#), result);
+
+    let result = read_match("[-a]+", r#"["a-", "b"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["a-"]"#), result);
+
+    let result = read_match("[a-]+", r#"["a-", "b"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["a-"]"#), result);
+
+    // characters with a special meaning in the `regex` crate are ordinary characters
+    let result = read_match("[&&~^]+", r#"["&~^", "a"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["&~^"]"#), result);
+
+    let result = read_match(r"[\p{Nd}\-\]x]+", r#"["1-]x", "y"]"#)?;
+    assert_eq!(diag_to_bytes(r#"["1-]x"]"#), result);
+
+    let result = read_match(r"\.\*\n", "[\".*\n\", \"a*\n\"]")?;
+    assert_eq!(diag_to_bytes("[\".*\n\"]"), result);
+
+    Ok(())
+}
+
+#[test]
+fn invalid() {
+    for regex in [
+        r"\d",
+        r"\w+",
+        r"\b",
+        "(?i)a",
+        "(?:a)",
+        "a*?",
+        "a{,2}",
+        "a{2",
+        "(a",
+        "a)",
+        "*a",
+        "[]",
+        "[a",
+        "[a-c-e]",
+        "[[a]]",
+        r"\p{Lx}",
+        r"\p{Greek}",
+        r"[\d]",
+        r"[a-\p{L}]",
+        "a{2}{3}",
+    ] {
+        assert!(
+            matches!(_match(sing_rel_path(), regex), Err(Error::Conversion(_))),
+            "{regex}"
+        );
+        assert!(
+            matches!(search(sing_rel_path(), regex), Err(Error::Conversion(_))),
+            "{regex}"
+        );
+    }
+
+    assert!(matches!(
+        _match(sing_rel_path(), "a(b"),
+        Err(Error::Conversion(e)) if e == "invalid I-Regexp `a(b`: expected `)` at column 4"
+    ));
+
+    assert!(matches!(
+        CborPath::parse("$[?match(@, '\\\\d')]"),
+        Err(Error::Syntax(_))
+    ));
+}
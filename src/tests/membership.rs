@@ -0,0 +1,217 @@
+use crate::{
+    builder::{_in, array_contains, nin, sing_abs_path, sing_rel_path, val},
+    tests::util::{diag_to_bytes, diag_to_cbor},
+    CborPath, Error,
+};
+
+fn read(cbor_path: &CborPath, values: &str) -> Result<Vec<u8>, Error> {
+    cbor_path.read_from_bytes(&diag_to_bytes(values))
+}
+
+#[test]
+fn literal_array() -> Result<(), Error> {
+    let values = r#"[
+        {"title": "a", "category": "fiction"},
+        {"title": "b", "category": "poetry"},
+        {"title": "c", "category": "essay"},
+        {"title": "d"}
+    ]"#;
+
+    let categories = diag_to_cbor(r#"["fiction", "poetry"]"#);
+
+    let cbor_path = CborPath::builder()
+        .filter(_in(sing_rel_path().key("category"), val(&*categories)))
+        .key("title")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["a", "b"]"#), read(&cbor_path, values)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(nin(sing_rel_path().key("category"), val(&*categories)))
+        .key("title")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["c", "d"]"#), read(&cbor_path, values)?);
+
+    // same equality as comparisons
+    let cbor_path = CborPath::builder()
+        .filter(_in(
+            sing_rel_path(),
+            val(&*diag_to_cbor("[1, [2, 3], 1(60)]")),
+        ))
+        .build();
+    assert_eq!(
+        diag_to_bytes(r#"[1.0, [2, 3.0], 0("1970-01-01T00:01:00Z")]"#),
+        read(
+            &cbor_path,
+            r#"[1.0, 2, [2, 3.0], 0("1970-01-01T00:01:00Z"), "1"]"#
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn array_path() -> Result<(), Error> {
+    let values = r#"{
+        "allowed": ["fiction", "poetry"],
+        "books": [
+            {"title": "a", "category": "fiction", "tags": ["urgent", "new"]},
+            {"title": "b", "category": "essay", "tags": ["new"]},
+            {"title": "c", "category": "poetry", "tags": "urgent"}
+        ]
+    }"#;
+
+    let cbor_path = CborPath::builder()
+        .key("books")
+        .filter(_in(
+            sing_rel_path().key("category"),
+            sing_abs_path().key("allowed"),
+        ))
+        .key("title")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["a", "c"]"#), read(&cbor_path, values)?);
+
+    // unlike the `contains` string function, only the elements of an array are tested
+    let cbor_path = CborPath::builder()
+        .key("books")
+        .filter(array_contains(sing_rel_path().key("tags"), val("urgent")))
+        .key("title")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["a"]"#), read(&cbor_path, values)?);
+
+    // a value which is not an array contains no element
+    let cbor_path = CborPath::builder()
+        .key("books")
+        .filter(nin(val("new"), sing_rel_path().key("tags")))
+        .key("title")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["c"]"#), read(&cbor_path, values)?);
+
+    Ok(())
+}
+
+#[test]
+fn conversion() -> Result<(), Error> {
+    let cbor_path = CborPath::from_value(&diag_to_cbor(
+        r#"["$", {"?": {"||": [
+            {"in": [["@", "category"], {"lit": ["fiction", "poetry"]}]},
+            {"nin": [["@", "year"], ["$", "years"]]}
+        ]}}]"#,
+    ))?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(
+                _in(
+                    sing_rel_path().key("category"),
+                    val(&*diag_to_cbor(r#"["fiction", "poetry"]"#))
+                ) | nin(sing_rel_path().key("year"), sing_abs_path().key("years"))
+            )
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        "$[?@.category in ['fiction','poetry'] || @.year nin $.years]",
+        cbor_path.to_string()
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+    assert_eq!(cbor_path, CborPath::parse(&cbor_path.to_string())?);
+
+    let cbor_path = CborPath::parse("$[?!(@ in [1, 'a', [true, null], []]) && length(@) in $.l]")?;
+    assert_eq!(
+        "$[?!(@ in [1,'a',[true,null],[]]) && length(@) in $.l]",
+        cbor_path.to_string()
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    let cbor_path = CborPath::parse("$[?@.tags contains 'urgent' && ['a', 'b'] contains @.c]")?;
+    assert_eq!(
+        CborPath::from_value(&diag_to_cbor(
+            r#"["$", {"?": {"&&": [
+                {"array_contains": [["@", "tags"], "urgent"]},
+                {"array_contains": [{"lit": ["a", "b"]}, ["@", "c"]]}
+            ]}}]"#
+        ))?,
+        cbor_path
+    );
+    assert_eq!(
+        CborPath::builder()
+            .filter(
+                array_contains(sing_rel_path().key("tags"), val("urgent"))
+                    & array_contains(
+                        val(&*diag_to_cbor(r#"["a", "b"]"#)),
+                        sing_rel_path().key("c")
+                    )
+            )
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        "$[?@.tags contains 'urgent' && ['a','b'] contains @.c]",
+        cbor_path.to_string()
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    // `contains` followed by parentheses is the string function
+    assert_ne!(
+        cbor_path,
+        CborPath::parse("$[?contains(@.tags, 'urgent')]")?
+    );
+
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"in": [["@"]]}}]"#)),
+        Err(Error::Conversion(_))
+    ));
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"in": [["@"], ["@", {"*": 1}]]}}]"#)),
+        Err(Error::Conversion(e)) if e == "Non-singular path `@[*]` cannot be used as a comparable"
+    ));
+    // a bare array is always a path
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(
+            r#"["$", {"?": {"in": [["@"], ["fiction", "poetry"]]}}]"#
+        )),
+        Err(Error::Conversion(_))
+    ));
+    assert!(matches!(
+        CborPath::parse("$[?@ in [@.a]]"),
+        Err(Error::Syntax(e)) if e == "Expected literal value at column 10"
+    ));
+    assert!(matches!(
+        CborPath::parse("$[?@ in $..a]"),
+        Err(Error::Syntax(e)) if e == "Expected singular query at column 9"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn literal_array_of_identifiers() -> Result<(), Error> {
+    // a literal array beginning with `$` or `@` is not a path
+    let cbor_path = CborPath::from_value(&diag_to_cbor(
+        r#"["$", {"?": {"in": [["@"], {"lit": ["$", "€"]}]}}]"#,
+    ))?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(_in(sing_rel_path(), val(&*diag_to_cbor(r#"["$", "€"]"#))))
+            .build(),
+        cbor_path
+    );
+    assert_eq!("$[?@ in ['$','€']]", cbor_path.to_string());
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+    assert_eq!(cbor_path, CborPath::parse(&cbor_path.to_string())?);
+    assert_eq!(
+        diag_to_bytes(r#"["$", "€"]"#),
+        read(&cbor_path, r#"["$", "£", "€", ["$", "€"]]"#)?
+    );
+
+    // while the same bare array is a singular path to the key `€` of the root
+    let cbor_path = CborPath::from_value(&diag_to_cbor(
+        r#"["$", {"?": {"in": [["@"], ["$", "€"]]}}]"#,
+    ))?;
+    assert_eq!("$[?@ in $.€]", cbor_path.to_string());
+    assert_eq!(
+        diag_to_bytes(r#"[2]"#),
+        read(&cbor_path, r#"{"a": 2, "b": 3, "€": [1, 2]}"#)?
+    );
+
+    Ok(())
+}
@@ -1,10 +1,19 @@
+mod arithmetic;
 mod array_append;
 mod array_index;
 mod builder;
 mod clear;
 mod conversion;
+mod cts;
 mod delete;
+mod display;
+mod function_registry;
 mod get_paths;
+mod iregexp;
+mod membership;
+mod parse;
+mod path;
 mod read;
 mod set;
+mod strings;
 mod util;
@@ -0,0 +1,242 @@
+use crate::{
+    builder::{
+        _match, abs_path, and, count, eq, gt, gte, length, lt, neq, not, or, rel_path, search,
+        segment, sing_abs_path, sing_rel_path, val, value,
+    },
+    CborPath, Error, FilterSelector, Segment, Selector, SliceSelector,
+};
+
+#[test]
+fn parse_segments() -> Result<(), Error> {
+    assert_eq!(CborPath::root(), CborPath::parse("$")?);
+
+    assert_eq!(
+        CborPath::builder()
+            .key("store")
+            .key("book")
+            .wildcard()
+            .key("author")
+            .build(),
+        CborPath::parse("$.store.book[*].author")?
+    );
+
+    assert_eq!(
+        CborPath::builder()
+            .descendant(segment().key("author"))
+            .build(),
+        CborPath::parse("$..author")?
+    );
+
+    assert_eq!(
+        CborPath::builder()
+            .key("store")
+            .descendant(segment().wildcard())
+            .build(),
+        CborPath::parse("$.store..*")?
+    );
+
+    assert_eq!(
+        CborPath::builder()
+            .key("o'k")
+            .key("é")
+            .key("\u{1F600}")
+            .child(segment().key("a").index(-1).wildcard())
+            .descendant(segment().key("b").index(0))
+            .build(),
+        CborPath::parse(r#"$['o\'k']["é"]['😀'][ 'a' , -1, * ]..["b",0]"#)?
+    );
+
+    assert_eq!(
+        CborPath::builder().key("a").key("b").build(),
+        CborPath::parse("$ .a ['b']")?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_slice() -> Result<(), Error> {
+    assert_eq!(
+        CborPath::builder()
+            .slice(0, 2, 1)
+            .slice(1, 5, 2)
+            .slice(5, 1, -2)
+            .build(),
+        CborPath::parse("$[0:2][1:5:2][5:1:-2]")?
+    );
+
+    assert_eq!(
+        CborPath::new(vec![
+            Segment::Child(vec![Selector::Slice(SliceSelector::new(None, None, 1))]),
+            Segment::Child(vec![Selector::Slice(SliceSelector::new(Some(1), None, 1))]),
+            Segment::Child(vec![Selector::Slice(SliceSelector::new(None, Some(-1), 1))]),
+            Segment::Child(vec![Selector::Slice(SliceSelector::new(None, None, -1))]),
+        ]),
+        CborPath::parse("$[:][1:][:-1][::-1]")?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_filter() -> Result<(), Error> {
+    assert_eq!(
+        CborPath::builder()
+            .key("store")
+            .key("book")
+            .filter(lt(sing_rel_path().key("price"), val(10)))
+            .key("title")
+            .build(),
+        CborPath::parse("$.store.book[?@.price < 10].title")?
+    );
+
+    assert_eq!(
+        CborPath::builder()
+            .descendant(segment().key("book"))
+            .filter(rel_path().key("isbn"))
+            .build(),
+        CborPath::parse("$..book[?@.isbn]")?
+    );
+
+    assert_eq!(
+        CborPath::builder()
+            .filter(or(
+                and(
+                    eq(sing_rel_path().key("a"), val("x")),
+                    neq(sing_rel_path().index(0), val(1.5)),
+                ),
+                not(abs_path().key("b")),
+            ))
+            .build(),
+        CborPath::parse("$[?@.a=='x' && @[0]!=1.5 || !$.b]")?
+    );
+
+    assert_eq!(
+        CborPath::builder()
+            .filter(and(
+                eq(sing_rel_path().key("a"), val(true)),
+                or(
+                    gt(sing_abs_path().key("b"), val(-1)),
+                    gte(val(()), val(false))
+                ),
+            ))
+            .build(),
+        CborPath::parse("$[?(@.a == true) && ($.b > -1 || null >= false)]")?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_functions() -> Result<(), Error> {
+    assert_eq!(
+        CborPath::builder()
+            .filter(gte(length(sing_rel_path().key("authors")), val(5)))
+            .filter(gte(count(rel_path().wildcard().key("authors")), val(5)))
+            .filter(eq(
+                value(rel_path().descendant(segment().key("color"))),
+                val("red")
+            ))
+            .filter(_match(sing_rel_path().key("date"), "1974-05-..")?)
+            .filter(not(search(sing_rel_path().key("author"), "[BR]ob")?))
+            .build(),
+        CborPath::parse(
+            "$[?length(@.authors) >= 5]\
+            [?count(@.*.authors) >= 5]\
+            [?value(@..color) == 'red']\
+            [?match(@.date, '1974-05-..')]\
+            [?!search(@.author, '[BR]ob')]"
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn parse_errors() {
+    fn error(jsonpath: &str) -> String {
+        match CborPath::parse(jsonpath) {
+            Err(Error::Syntax(e)) => e,
+            result => panic!("unexpected result for `{jsonpath}`: {result:?}"),
+        }
+    }
+
+    assert_eq!("Expected `$` at column 1", error("a.b"));
+    assert_eq!("Expected `$` at column 1", error(" $"));
+    assert_eq!("Unexpected character ` ` at column 2", error("$ "));
+    assert_eq!("Expected member name at column 3", error("$.1"));
+    assert_eq!("Expected `,` or `]` at column 6", error("$['a'"));
+    assert_eq!("Unterminated string literal at column 6", error("$['a]"));
+    assert_eq!("Invalid integer `-0` at column 3", error("$[-0]"));
+    assert_eq!(
+        "Integer out of range at column 3",
+        error("$[9007199254740992]")
+    );
+    assert_eq!("Expected singular query at column 4", error("$[?@.* == 1]"));
+    assert_eq!(
+        "Expected test expression or comparison at column 4",
+        error("$[?length(@) ]")
+    );
+    assert_eq!("Unknown function `foo` at column 4", error("$[?foo(@)]"));
+    assert_eq!(
+        "Expected filter query at column 10",
+        error("$[?count(1) == 1]")
+    );
+    assert_eq!("Invalid escape sequence at column 5", error(r"$['a\x']"));
+}
+
+#[test]
+fn parse_nesting_depth() -> Result<(), Error> {
+    let parens = |depth: usize| format!("$[?{}@{}]", "(".repeat(depth), ")".repeat(depth));
+    let filters = |depth: usize| format!("${}", "[?@".repeat(depth) + &"]".repeat(depth));
+
+    CborPath::parse(&parens(63))?;
+    CborPath::parse(&filters(63))?;
+
+    for jsonpath in [
+        parens(100_000),
+        filters(100_000),
+        format!(
+            "$[?{}@{}==1]",
+            "length(".repeat(100_000),
+            ")".repeat(100_000)
+        ),
+        format!("$[?@ in {}1{}]", "[".repeat(100_000), "]".repeat(100_000)),
+        format!("$[?{}", "(".repeat(100_000)),
+    ] {
+        assert!(matches!(
+            CborPath::parse(&jsonpath),
+            Err(Error::Syntax(e)) if e.starts_with("Maximum nesting depth exceeded")
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn parse_type_errors() {
+    // ValueType functions cannot be used as test expressions, even in a nested filter
+    assert!(CborPath::parse("$[?length(@)]").is_err());
+    assert!(CborPath::parse("$[?@.a[?upper(@)]]").is_err());
+    assert!(CborPath::parse("$[?count(@[?value(@.b)])==1]").is_err());
+    // LogicalType functions cannot be compared
+    assert!(CborPath::parse("$[?match(@,'a')==true]").is_err());
+    assert!(CborPath::parse("$[?@.a[?concat()=='a']]").is_err());
+}
+
+#[test]
+fn from_str() -> Result<(), Error> {
+    let cbor_path: CborPath = "$.a[?@.b]".parse()?;
+    assert_eq!(
+        CborPath::new(vec![
+            Segment::Child(vec![Selector::Key(crate::KeySelector::new(
+                crate::builder::IntoCborOwned::into("a")
+            ))]),
+            Segment::Child(vec![Selector::Filter(FilterSelector::new(
+                crate::BooleanExpr::Path(rel_path().key("b").build_path())
+            ))]),
+        ]),
+        cbor_path
+    );
+    Ok(())
+}
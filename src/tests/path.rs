@@ -0,0 +1,192 @@
+use crate::{
+    builder::segment,
+    tests::util::{diag_to_bytes, diag_to_cbor},
+    CborPath, Error, Path,
+};
+use cbor_data::Cbor;
+
+#[test]
+fn to_normalized_path() -> Result<(), Error> {
+    assert_eq!("$", Path::default().to_normalized_path());
+
+    assert_eq!(
+        "$['store']['book'][0]",
+        Path::default()
+            .key("store")
+            .key("book")
+            .idx(0)
+            .to_normalized_path()
+    );
+
+    assert_eq!(
+        r#"$['it\'s']['a\\b']['\n\u0001']['"x"'][12][h'0102'][1.5]"#,
+        Path::default()
+            .key("it's")
+            .key("a\\b")
+            .key("\n\u{1}")
+            .key("\"x\"")
+            .key(12)
+            .key([1u8, 2].as_slice())
+            .key(1.5)
+            .to_normalized_path()
+    );
+
+    let value = diag_to_bytes(r#"{"store": {"book": [{"title": "a"}, {"title": "b"}]}}"#);
+    let cbor_path = CborPath::parse("$..title")?;
+    let paths = cbor_path
+        .get_paths_from_bytes(&value)?
+        .iter()
+        .map(Path::to_normalized_path)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            "$['store']['book'][0]['title']",
+            "$['store']['book'][1]['title']"
+        ],
+        paths
+    );
+
+    Ok(())
+}
+
+#[test]
+fn to_json_pointer() -> Result<(), Error> {
+    assert_eq!("", Path::default().to_json_pointer()?);
+
+    assert_eq!(
+        "/store/book/0",
+        Path::default()
+            .key("store")
+            .key("book")
+            .idx(0)
+            .to_json_pointer()?
+    );
+
+    assert_eq!(
+        "/a~1b/m~0n/ /",
+        Path::default()
+            .key("a/b")
+            .key("m~n")
+            .key(" ")
+            .key("")
+            .to_json_pointer()?
+    );
+
+    assert!(matches!(
+        Path::default().key("a").key(12).to_json_pointer(),
+        Err(Error::Conversion(_))
+    ));
+
+    assert!(matches!(
+        Path::default().key("a").embedded().to_json_pointer(),
+        Err(Error::Conversion(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn from_json_pointer() -> Result<(), Error> {
+    assert_eq!(Path::default(), Path::from_json_pointer("")?);
+    assert_eq!(Path::default().key(""), Path::from_json_pointer("/")?);
+
+    assert_eq!(
+        Path::default().key("store").key("book").idx(0),
+        Path::from_json_pointer("/store/book/0")?
+    );
+
+    assert_eq!(
+        Path::default().key("a/b").key("m~n").key("01").key("-"),
+        Path::from_json_pointer("/a~1b/m~0n/01/-")?
+    );
+
+    let path = Path::default().key("a/b").key("~01").idx(12);
+    assert_eq!(path, Path::from_json_pointer(&path.to_json_pointer()?)?);
+
+    assert!(matches!(
+        Path::from_json_pointer("a"),
+        Err(Error::Syntax(e)) if e == "Expected `/` at column 1"
+    ));
+    assert!(matches!(
+        Path::from_json_pointer("/ab/c~2"),
+        Err(Error::Syntax(e)) if e == "Invalid escape sequence at column 6"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn resolve() -> Result<(), Error> {
+    let value = diag_to_cbor(r#"{"a": [{"b": 1}, {"c": 2}], 12: {h'01': true}}"#);
+
+    assert_eq!(Some(value.as_ref()), Path::default().resolve(&value));
+    assert_eq!(
+        Some(diag_to_cbor("2").as_ref()),
+        Path::default().key("a").idx(1).key("c").resolve(&value)
+    );
+    assert_eq!(
+        Some(diag_to_cbor("true").as_ref()),
+        Path::default()
+            .key(12)
+            .key([1u8].as_slice())
+            .resolve(&value)
+    );
+    assert_eq!(None, Path::default().key("a").idx(2).resolve(&value));
+    assert_eq!(None, Path::default().key("b").resolve(&value));
+    assert_eq!(None, Path::default().idx(0).resolve(&value));
+    assert_eq!(None, Path::default().key("a").embedded().resolve(&value));
+
+    let cbor_path = CborPath::parse("$..c")?;
+    for path in cbor_path.get_paths(&value) {
+        assert_eq!(cbor_path.read(&value), vec![path.resolve(&value).unwrap()]);
+    }
+
+    let bytes = diag_to_bytes(r#"{"payload": 24(<<[1, {"sub": 2}]>>)}"#);
+    let cbor = Cbor::checked(&bytes)?;
+    assert_eq!(
+        Some(diag_to_cbor("2").as_ref()),
+        Path::default()
+            .key("payload")
+            .embedded()
+            .idx(1)
+            .key("sub")
+            .resolve(cbor)
+    );
+    assert_eq!(
+        "$['payload'][<<>>][1]['sub']",
+        CborPath::builder()
+            .key("payload")
+            .embedded()
+            .descendant(segment().key("sub"))
+            .build()
+            .get_paths(cbor)
+            .first()
+            .map_or(String::new(), Path::to_normalized_path)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn to_cbor_path() -> Result<(), Error> {
+    let value = diag_to_cbor(r#"{"a": [{"b": 1}, {"c": 2}]}"#);
+
+    let path = Path::default().key("a").idx(1).key("c");
+    let cbor_path = path.to_cbor_path();
+    assert_eq!(CborPath::parse("$.a[1].c")?, cbor_path);
+    assert_eq!(vec![path.clone()], cbor_path.get_paths(&value));
+
+    let new_value = diag_to_cbor("3");
+    assert_eq!(
+        Some(diag_to_cbor(r#"{"a": [{"b": 1}, {"c": 3}]}"#)),
+        cbor_path.set(&value, &new_value)
+    );
+    assert_eq!(
+        Some(diag_to_cbor(r#"{"a": [{"b": 1}, {}]}"#)),
+        cbor_path.delete(&value)
+    );
+
+    assert_eq!(CborPath::root(), Path::default().to_cbor_path());
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use crate::{
         self, _match, and, eq, gt, gte, lt, lte, neq, or, rel_path, search, segment, sing_abs_path,
         sing_rel_path, val,
     },
-    tests::util::{bytes_to_diag, diag_to_bytes},
+    tests::util::{bytes_to_diag, diag_to_bytes, diag_to_cbor},
     CborPath, Error,
 };
 use cbor_data::Cbor;
@@ -330,6 +330,18 @@ fn filter() -> Result<(), Error> {
     let result = cbor_path.read_from_bytes(&value)?;
     assert_eq!(diag_to_bytes(r#"[2, 3]"#), result);
 
+    // ["$", "o", {"?": {"&&": [{">": [["@"], 1]}, {"<": ["@", 4]}, {"!=": ["@", 3]}]}}]
+    let cbor_path = CborPath::builder()
+        .key("o")
+        .filter(
+            gt(sing_rel_path(), val(1))
+                & lt(sing_rel_path(), val(4))
+                & neq(sing_rel_path(), val(3)),
+        )
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[2]"#), result);
+
     // ["$", "o", {"?": {"||": [["@", "u"], ["@", "x"]]}}]
     let cbor_path = CborPath::builder()
         .key("o")
@@ -979,3 +991,413 @@ fn store() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn read_iter() -> Result<(), Error> {
+    let value = diag_to_cbor(
+        r#"{"o": {"j": 1, "k": 2}, "a": [5, 3, [{"j": 4}, {"k": 6}]], "e": [], "s": "str"}"#,
+    );
+
+    for query in [
+        "$",
+        "$.o.j",
+        "$.*",
+        "$..*",
+        "$..j",
+        "$.a[1:]",
+        "$.a[-1]",
+        "$..[?@.j]",
+        "$..[?@ > 2]",
+        "$.s.*",
+        "$.missing",
+    ] {
+        let cbor_path = CborPath::parse(query)?;
+        assert_eq!(
+            cbor_path.read(&value),
+            cbor_path.read_iter(&value).collect::<Vec<_>>(),
+            "{query}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn exists_and_first() -> Result<(), Error> {
+    let value = diag_to_cbor(r#"{"o": {"j": 1, "k": 2}, "a": [5, 3, [{"j": 4}]]}"#);
+
+    let cbor_path = CborPath::parse("$..j")?;
+    assert!(cbor_path.exists(&value));
+    assert_eq!(Some(diag_to_cbor("1").as_ref()), cbor_path.first(&value));
+
+    let cbor_path = CborPath::parse("$..[?@ > 4]")?;
+    assert!(cbor_path.exists(&value));
+    assert_eq!(Some(diag_to_cbor("5").as_ref()), cbor_path.first(&value));
+
+    let cbor_path = CborPath::parse("$..missing")?;
+    assert!(!cbor_path.exists(&value));
+    assert_eq!(None, cbor_path.first(&value));
+
+    // the returned nodes only borrow the document, not the path
+    let first = CborPath::parse("$.a[0]")?.first(&value);
+    let nodes: Vec<_> = {
+        let cbor_path = CborPath::parse("$.o.*")?;
+        cbor_path.read_iter(&value).collect()
+    };
+    assert_eq!(Some(diag_to_cbor("5").as_ref()), first);
+    assert_eq!(CborPath::parse("$.o.*")?.read(&value), nodes);
+
+    Ok(())
+}
+
+#[test]
+fn numeric_comparison() -> Result<(), Error> {
+    let value = diag_to_bytes(
+        r#"[-2, -1, 0, 1, 1.0, 1.5, -1.5, 18446744073709551615, 18446744073709551616.0, NaN]"#,
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(lt(sing_rel_path(), val(1)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[-2, -1, 0, -1.5]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path(), val(1)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[1, 1.0]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(gt(sing_rel_path(), val(-1.5)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(
+        diag_to_bytes(r#"[-1, 0, 1, 1.0, 1.5, 18446744073709551615, 18446744073709551616.0]"#),
+        result
+    );
+
+    // 18446744073709551615 (u64::MAX) cannot be represented exactly as a float
+    let cbor_path = CborPath::builder()
+        .filter(lt(sing_rel_path(), val(18446744073709551616.0)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(
+        diag_to_bytes(r#"[-2, -1, 0, 1, 1.0, 1.5, -1.5, 18446744073709551615]"#),
+        result
+    );
+
+    let cbor_path = CborPath::parse("$[?@ == -1.0 || @ >= 18446744073709551615]")?;
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(
+        diag_to_bytes(r#"[-1, 18446744073709551615, 18446744073709551616.0]"#),
+        result
+    );
+
+    Ok(())
+}
+
+#[test]
+fn bignum_and_decimal_comparison() -> Result<(), Error> {
+    // 2^64, -2^64-1, 3.14, 1.50, 1.5e3
+    let value = diag_to_bytes(
+        r#"[2(h'010000000000000000'), 3(h'010000000000000000'), 4([-2, 314]), 4([-2, 150]), 4([1, 150]), 1.5, 18446744073709551615]"#,
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path(), val(1.5)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[4([-2, 150]), 1.5]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(gt(sing_rel_path(), val(18446744073709551615u64)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[2(h'010000000000000000')]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(lt(sing_rel_path(), builder::decimal(315, -2)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(
+        diag_to_bytes(r#"[3(h'010000000000000000'), 4([-2, 314]), 4([-2, 150]), 1.5]"#),
+        result
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path(), val(18446744073709551616u128)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[2(h'010000000000000000')]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path(), val(-18446744073709551617i128)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[3(h'010000000000000000')]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path(), val(1500)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[4([1, 150])]"#), result);
+
+    Ok(())
+}
+
+#[test]
+fn datetime_comparison() -> Result<(), Error> {
+    let value = diag_to_bytes(
+        r#"[
+            {"id": 1, "created": 0("2023-03-01T10:00:00Z")},
+            {"id": 2, "created": 1(1677664800)},
+            {"id": 3, "created": 0("2023-03-01T12:00:00.5+02:00")},
+            {"id": 4, "created": 1(1677672000.25)},
+            {"id": 5, "created": "2023-03-01T10:00:00Z"}
+        ]"#,
+    );
+
+    // 2023-03-01T10:00:00Z
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path().key("created"), builder::epoch(1677664800)))
+        .key("id")
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[1, 2]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(gt(
+            sing_rel_path().key("created"),
+            builder::datetime("2023-03-01T10:00:00.1Z")?,
+        ))
+        .key("id")
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[3, 4]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(lte(
+            sing_rel_path().key("created"),
+            builder::datetime("2023-03-01T11:00:00+01:00")?,
+        ))
+        .key("id")
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[1, 2]"#), result);
+
+    // an epoch-based date/time is compared numerically with a plain number
+    let cbor_path = CborPath::builder()
+        .filter(gte(sing_rel_path().key("created"), val(1677664800)))
+        .key("id")
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[2, 4]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path().key("created"), val(1677672000.25)))
+        .key("id")
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[4]"#), result);
+
+    assert!(builder::datetime("2023-03-01").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn tag_selector() -> Result<(), Error> {
+    let value = diag_to_bytes(
+        r#"{"a": [32("http://a"), "b", 32("http://c"), 55799(32("http://d"))], "e": {"f": 32("http://f"), "g": 1(0)}}"#,
+    );
+
+    let cbor_path = CborPath::builder().key("a").tag(32).build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[32("http://a"), 32("http://c")]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .descendant(segment().tag(32).tag(1))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(
+        diag_to_bytes(r#"[32("http://a"), 32("http://c"), 32("http://f"), 1(0)]"#),
+        result
+    );
+
+    let cbor_path = CborPath::from_value(&diag_to_cbor(r#"["$", "e", {"tag": 1}]"#))?;
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[1(0)]"#), result);
+
+    let cbor_path = CborPath::builder().key("a").index(1).tag(32).build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[]"#), result);
+
+    Ok(())
+}
+
+#[test]
+fn tag_function() -> Result<(), Error> {
+    let value = diag_to_bytes(
+        r#"[{"id": 1, "v": 32("http://a")}, {"id": 2, "v": 55799(32("http://b"))}, {"id": 3, "v": "c"}]"#,
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(builder::tag(sing_rel_path().key("v")), val(32)))
+        .key("id")
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[1]"#), result);
+
+    let cbor_path = CborPath::parse("$[?tag(@.v) > 32].id")?;
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[2]"#), result);
+
+    // untagged value: the result is Nothing
+    let cbor_path = CborPath::from_value(&diag_to_cbor(
+        r#"["$", {"?": {"==": [{"tag": ["@", "v"]}, {"count": ["@", "missing"]}]}}, "id"]"#,
+    ))?;
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[]"#), result);
+
+    Ok(())
+}
+
+#[test]
+fn embedded_selector() -> Result<(), Error> {
+    let value = diag_to_bytes(
+        r#"{"payload": 24(<<{"sub": 1, "c": [2, 3]}>>), "plain": <<[4, 5]>>, "bignum": 2(h'01'), "raw": h'ff'}"#,
+    );
+
+    let cbor_path =
+        CborPath::from_value(&diag_to_cbor(r#"["$", "payload", {"embedded": 1}, "sub"]"#))?;
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[1]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .key("plain")
+        .embedded()
+        .index(-1)
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[5]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .wildcard()
+        .embedded()
+        .descendant(segment().index(0))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[2, 4]"#), result);
+
+    // only selected explicitly
+    let cbor_path = CborPath::builder().descendant(segment().key("sub")).build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .child(segment().key("bignum").key("raw"))
+        .embedded()
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[]"#), result);
+
+    Ok(())
+}
+
+#[test]
+fn chunked_strings() -> Result<(), Error> {
+    // `diag_to_cbor` would concatenate the chunks
+    let value = diag_to_bytes(
+        r#"{"a": [(_ "ji", "ngle"), (_ "k", "ilo"), "jingle", (_ h'01', h'02')], (_ "b", "c"): 1, "d": {(_ "x", "y"): "z"}, "e": {"xy": "z"}}"#,
+    );
+
+    let cbor_path = CborPath::builder().key("bc").build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[1]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .key("a")
+        .filter(eq(sing_rel_path(), val("jingle")))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[(_ "ji", "ngle"), "jingle"]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .key("a")
+        .filter(eq(sing_rel_path(), val([1u8, 2].as_slice())))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[(_ h'01', h'02')]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .key("a")
+        .filter(gt(sing_rel_path(), val("jz")))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[(_ "k", "ilo")]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .key("a")
+        .filter(_match(sing_rel_path(), "j.*e")?)
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[(_ "ji", "ngle"), "jingle"]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .key("a")
+        .filter(search(sing_rel_path(), "il")?)
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[(_ "k", "ilo")]"#), result);
+
+    let cbor_path = CborPath::builder()
+        .key("a")
+        .filter(eq(builder::length(sing_rel_path()), val(6)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"[(_ "ji", "ngle"), "jingle"]"#), result);
+
+    // maps with chunked keys
+    let cbor_path = CborPath::builder()
+        .filter(eq(sing_rel_path(), sing_abs_path().key("e")))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(
+        diag_to_bytes(r#"[{(_ "x", "y"): "z"}, {"xy": "z"}]"#),
+        result
+    );
+
+    Ok(())
+}
+
+#[test]
+fn string_length() -> Result<(), Error> {
+    let value =
+        diag_to_bytes(r#"["été", (_ "é", "t", "é"), "日本", 'été', (_ h'c3a9', h'74'), "abc"]"#);
+
+    // text strings: number of Unicode scalar values
+    let cbor_path = CborPath::builder()
+        .filter(eq(builder::length(sing_rel_path()), val(3)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(
+        diag_to_bytes(r#"["été", (_ "é", "t", "é"), (_ h'c3a9', h'74'), "abc"]"#),
+        result
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(builder::length(sing_rel_path()), val(2)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"["日本"]"#), result);
+
+    // byte strings: number of bytes
+    let cbor_path = CborPath::builder()
+        .filter(eq(builder::length(sing_rel_path()), val(5)))
+        .build();
+    let result = cbor_path.read_from_bytes(&value)?;
+    assert_eq!(diag_to_bytes(r#"['été']"#), result);
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use crate::{
     builder::{segment, IntoCborOwned},
-    tests::util::{cbor_to_diag, diag_to_cbor},
-    CborPath,
+    tests::util::{cbor_to_diag, diag_to_bytes, diag_to_cbor},
+    CborPath, Error,
 };
 use cbor_data::CborOwned;
 
@@ -114,3 +114,53 @@ fn no_match() {
 
     assert!(result.is_none());
 }
+
+#[test]
+fn tagged_containers() {
+    let cbor = diag_to_cbor(r#"55799({"a": 259({1: 32([{"b": 1}, 2])}), "c": 1(0)})"#);
+    let new_value: CborOwned = IntoCborOwned::into(3);
+
+    let cbor_path = CborPath::builder()
+        .key("a")
+        .key(1)
+        .index(0)
+        .key("b")
+        .build();
+    let result = cbor_path.set(&cbor, &new_value).unwrap();
+
+    assert_eq!(
+        diag_to_cbor(r#"55799({"a": 259({1: 32([{"b": 3}, 2])}), "c": 1(0)})"#),
+        result
+    );
+}
+
+#[test]
+fn embedded() -> Result<(), Error> {
+    // `diag_to_cbor` would inline the tag 24 byte strings
+    let cbor = diag_to_bytes(r#"{"payload": 24(<<{"sub": 1, "c": 2}>>), "d": 3}"#);
+    let new_value = diag_to_bytes("4");
+
+    let cbor_path = CborPath::builder()
+        .key("payload")
+        .embedded()
+        .key("sub")
+        .build();
+    let result = cbor_path.set_from_bytes(&cbor, &new_value)?;
+
+    assert_eq!(
+        Some(diag_to_bytes(
+            r#"{"payload": 24(<<{"sub": 4, "c": 2}>>), "d": 3}"#
+        )),
+        result
+    );
+
+    let cbor_path = CborPath::builder().key("payload").embedded().build();
+    let result = cbor_path.set_from_bytes(&cbor, &new_value)?;
+
+    assert_eq!(
+        Some(diag_to_bytes(r#"{"payload": 24(<<4>>), "d": 3}"#)),
+        result
+    );
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use crate::{
+    builder::{
+        concat, contains, ends_with, eq, lower, not, sing_rel_path, starts_with, substring, upper,
+        val, ComparableBuilder,
+    },
+    tests::util::{diag_to_bytes, diag_to_cbor},
+    CborPath, Error,
+};
+
+fn read(cbor_path: &CborPath, values: &str) -> Result<Vec<u8>, Error> {
+    cbor_path.read_from_bytes(&diag_to_bytes(values))
+}
+
+#[test]
+fn string_tests() -> Result<(), Error> {
+    let values = r#"["foobar", "barfoo", "fbar", h'666f6f62', 12, "FOO"]"#;
+
+    let cbor_path = CborPath::builder()
+        .filter(starts_with(sing_rel_path(), val("foo")))
+        .build();
+    assert_eq!(diag_to_bytes(r#"["foobar"]"#), read(&cbor_path, values)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(ends_with(sing_rel_path(), val("foo")))
+        .build();
+    assert_eq!(diag_to_bytes(r#"["barfoo"]"#), read(&cbor_path, values)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(contains(sing_rel_path(), val("bar")))
+        .build();
+    assert_eq!(
+        diag_to_bytes(r#"["foobar", "barfoo", "fbar"]"#),
+        read(&cbor_path, values)?
+    );
+
+    // byte strings are only compared with byte strings
+    let cbor_path = CborPath::builder()
+        .filter(starts_with(sing_rel_path(), val("foo".as_bytes())))
+        .build();
+    assert_eq!(diag_to_bytes(r#"[h'666f6f62']"#), read(&cbor_path, values)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(contains(sing_rel_path(), val("ob".as_bytes())))
+        .build();
+    assert_eq!(diag_to_bytes(r#"[h'666f6f62']"#), read(&cbor_path, values)?);
+
+    // case-insensitive equality
+    let cbor_path = CborPath::builder()
+        .filter(eq(lower(sing_rel_path()), val("foo")))
+        .build();
+    assert_eq!(diag_to_bytes(r#"["FOO"]"#), read(&cbor_path, values)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(not(starts_with(upper(sing_rel_path()), val("F"))))
+        .build();
+    assert_eq!(
+        diag_to_bytes(r#"["barfoo", h'666f6f62', 12]"#),
+        read(&cbor_path, values)?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn substring_and_concat() -> Result<(), Error> {
+    let values = r#"[
+        {"a": "héllo", "b": "wörld"},
+        {"a": h'0102', "b": h'0304'},
+        {"a": "hé", "b": h'0304'}
+    ]"#;
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            substring(sing_rel_path().key("a"), val(1), Some(val(3))),
+            val("él"),
+        ))
+        .key("a")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["héllo"]"#), read(&cbor_path, values)?);
+
+    // negative indices count from the end of the string and indices are clamped
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            substring(sing_rel_path().key("b"), val(-2), None),
+            val("ld"),
+        ))
+        .key("b")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["wörld"]"#), read(&cbor_path, values)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            substring(sing_rel_path().key("b"), val(-10), Some(val(1))),
+            val([3u8].as_slice()),
+        ))
+        .key("b")
+        .build();
+    assert_eq!(
+        diag_to_bytes(r#"[h'0304', h'0304']"#),
+        read(&cbor_path, values)?
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            substring(sing_rel_path().key("a"), val(3), Some(val(1))),
+            val(""),
+        ))
+        .key("a")
+        .build();
+    assert_eq!(
+        diag_to_bytes(r#"["héllo", "hé"]"#),
+        read(&cbor_path, values)?
+    );
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            concat([sing_rel_path().key("a"), sing_rel_path().key("b")]),
+            val("héllowörld"),
+        ))
+        .key("a")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["héllo"]"#), read(&cbor_path, values)?);
+
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            concat([sing_rel_path().key("a"), sing_rel_path().key("b")]),
+            val([1u8, 2, 3, 4].as_slice()),
+        ))
+        .key("a")
+        .build();
+    assert_eq!(diag_to_bytes(r#"[h'0102']"#), read(&cbor_path, values)?);
+
+    // mixed text and byte strings return nothing
+    let cbor_path = CborPath::builder()
+        .filter(eq(
+            concat([sing_rel_path().key("a"), sing_rel_path().key("b")]),
+            sing_rel_path().key("c"),
+        ))
+        .key("a")
+        .build();
+    assert_eq!(diag_to_bytes(r#"["hé"]"#), read(&cbor_path, values)?);
+
+    Ok(())
+}
+
+#[test]
+fn conversion() -> Result<(), Error> {
+    let cbor_path = CborPath::from_value(&diag_to_cbor(
+        r#"["$", {"?": {"&&": [
+            {"starts_with": [{"lower": ["@", "a"]}, "x"]},
+            {"==": [{"substring": [["@", "b"], 0, 2]}, {"concat": ["a", {"upper": ["@", "c"]}, "c"]}]}
+        ]}}]"#,
+    ))?;
+    assert_eq!(
+        CborPath::builder()
+            .filter(
+                starts_with(lower(sing_rel_path().key("a")), val("x"))
+                    & eq(
+                        substring(sing_rel_path().key("b"), val(0), Some(val(2))),
+                        concat([val("a"), upper(sing_rel_path().key("c")), val("c")]),
+                    )
+            )
+            .build(),
+        cbor_path
+    );
+    assert_eq!(
+        "$[?starts_with(lower(@.a),'x') && substring(@.b,0,2)==concat('a',upper(@.c),'c')]",
+        cbor_path.to_string()
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+    assert_eq!(cbor_path, CborPath::parse(&cbor_path.to_string())?);
+
+    let cbor_path =
+        CborPath::parse("$[?ends_with(@, 'a') || contains(@.b, $.c)][?substring(@, -1)=='z']")?;
+    assert_eq!(
+        "$[?ends_with(@,'a') || contains(@.b,$.c)][?substring(@,-1)=='z']",
+        cbor_path.to_string()
+    );
+    assert_eq!(cbor_path, CborPath::from_value(&cbor_path.to_cbor())?);
+
+    // type errors
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"lower": ["@"]}}]"#)),
+        Err(Error::Conversion(e)) if e == "Function `lower(@)` of type ValueType cannot be used as a test expression, it must be compared"
+    ));
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"==": [{"contains": [["@"], "a"]}, true]}}]"#)),
+        Err(Error::Conversion(e)) if e == "Function `contains(@,'a')` of type LogicalType cannot be used as a comparable"
+    ));
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(r#"["$", {"?": {"==": [{"concat": []}, "a"]}}]"#)),
+        Err(Error::Conversion(e)) if e == "Function `concat` expects at least one argument"
+    ));
+    assert!(matches!(
+        CborPath::builder().filter(eq(concat(Vec::<ComparableBuilder>::new()), val("a"))).try_build(),
+        Err(Error::Conversion(e)) if e == "Function `concat` expects at least one argument"
+    ));
+    assert!(matches!(
+        CborPath::from_value(&diag_to_cbor(
+            r#"["$", {"?": {"==": [{"substring": [["@"]]}, "a"]}}]"#
+        )),
+        Err(Error::Conversion(_))
+    ));
+    assert!(matches!(
+        CborPath::parse("$[?starts_with(@)]"),
+        Err(Error::Syntax(e)) if e == "Function `starts_with` expects 2 argument(s) at column 4"
+    ));
+    assert!(matches!(
+        CborPath::parse("$[?substring(@,1,2,3)=='a']"),
+        Err(Error::Syntax(e)) if e == "Function `substring` expects 2 or 3 arguments at column 4"
+    ));
+    assert!(CborPath::parse("$[?lower(@)]").is_err());
+
+    Ok(())
+}
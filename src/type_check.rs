@@ -0,0 +1,170 @@
+use crate::{
+    Argument, BooleanExpr, Comparable, Error, FilterPath, Function, RegexArgument, Segment,
+    Selector,
+};
+
+/// Type of a function parameter or of a function expression result
+///
+/// cf. https://www.rfc-editor.org/rfc/rfc9535.html#name-type-system-for-function-ex
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionType {
+    /// A `CBOR` value or `Nothing`
+    Value,
+    /// `true` or `false`
+    Logical,
+    /// A list of nodes, only as a parameter type
+    Nodes,
+}
+
+impl Function {
+    /// Type of the result, never [`FunctionType::Nodes`]
+    pub fn result_type(&self) -> FunctionType {
+        match self {
+            Function::Length(_)
+            | Function::Count(_)
+            | Function::Value(_)
+            | Function::Tag(_)
+            | Function::Arithmetic(..)
+            | Function::Numeric(..)
+            | Function::CaseConversion(..)
+            | Function::Substring(..)
+            | Function::Concat(_) => FunctionType::Value,
+            Function::Match(..) | Function::Search(..) | Function::StringTest(..) => {
+                FunctionType::Logical
+            }
+            Function::User(definition, _) => definition.result_type(),
+        }
+    }
+}
+
+/// Well-typedness of filter expressions,
+/// checked once the whole expression is built
+pub(crate) trait TypeCheck {
+    /// Returns an [`Error::Conversion`] describing the first ill-typed sub-expression
+    fn type_check(&self) -> Result<(), Error>;
+}
+
+impl TypeCheck for [Segment] {
+    fn type_check(&self) -> Result<(), Error> {
+        for segment in self {
+            let (Segment::Child(selectors) | Segment::Descendant(selectors)) = segment;
+            for selector in selectors {
+                if let Selector::Filter(filter) = selector {
+                    filter.get_boolean_expr().type_check()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TypeCheck for FilterPath {
+    fn type_check(&self) -> Result<(), Error> {
+        match self {
+            FilterPath::Abs(path) => path.get_segments().type_check(),
+            FilterPath::Rel(path) => path.get_segments().type_check(),
+        }
+    }
+}
+
+/// A boolean expression is used as a test expression:
+/// only paths and functions of type `LogicalType` can be tested
+impl TypeCheck for BooleanExpr {
+    fn type_check(&self) -> Result<(), Error> {
+        match self {
+            BooleanExpr::Or(left, right) | BooleanExpr::And(left, right) => {
+                left.type_check()?;
+                right.type_check()
+            }
+            BooleanExpr::Not(expr) => expr.type_check(),
+            BooleanExpr::Comparison(comparison) => {
+                comparison.get_left().type_check()?;
+                comparison.get_right().type_check()
+            }
+            BooleanExpr::Membership(membership) => {
+                membership.get_value().type_check()?;
+                membership.get_array().type_check()
+            }
+            BooleanExpr::Path(path) => path.type_check(),
+            BooleanExpr::Function(function) => {
+                if function.result_type() == FunctionType::Value {
+                    return Err(Error::Conversion(format!(
+                        "Function `{function}` of type ValueType cannot be used as a test expression, it must be compared"
+                    )));
+                }
+                function.type_check()
+            }
+        }
+    }
+}
+
+/// A comparable is used where a value is expected:
+/// only values, singular paths and functions of type `ValueType` are allowed
+impl TypeCheck for Comparable {
+    fn type_check(&self) -> Result<(), Error> {
+        match self {
+            Comparable::Value(_) | Comparable::SingularPath(_) => Ok(()),
+            Comparable::Function(function) => {
+                if function.result_type() == FunctionType::Logical {
+                    return Err(Error::Conversion(format!(
+                        "Function `{function}` of type LogicalType cannot be used as a comparable"
+                    )));
+                }
+                function.type_check()
+            }
+        }
+    }
+}
+
+/// Arguments of a function
+impl TypeCheck for Function {
+    fn type_check(&self) -> Result<(), Error> {
+        match self {
+            Function::Length(comparable)
+            | Function::Tag(comparable)
+            | Function::Numeric(_, comparable)
+            | Function::CaseConversion(_, comparable) => comparable.type_check(),
+            Function::Arithmetic(_, left, right) | Function::StringTest(_, left, right) => {
+                left.type_check()?;
+                right.type_check()
+            }
+            Function::Substring(comparable, start, end) => {
+                comparable.type_check()?;
+                start.type_check()?;
+                match end {
+                    Some(end) => end.type_check(),
+                    None => Ok(()),
+                }
+            }
+            Function::Concat(comparables) => {
+                if comparables.is_empty() {
+                    return Err(Error::Conversion(
+                        "Function `concat` expects at least one argument".to_owned(),
+                    ));
+                }
+                for comparable in comparables {
+                    comparable.type_check()?;
+                }
+                Ok(())
+            }
+            Function::Count(path) | Function::Value(path) => path.type_check(),
+            Function::Match(comparable, regex) | Function::Search(comparable, regex) => {
+                comparable.type_check()?;
+                match regex {
+                    RegexArgument::Literal(_) => Ok(()),
+                    RegexArgument::Comparable(regex) => regex.type_check(),
+                }
+            }
+            Function::User(_, arguments) => {
+                for argument in arguments {
+                    match argument {
+                        Argument::Value(comparable) => comparable.type_check()?,
+                        Argument::Logical(expr) => expr.type_check()?,
+                        Argument::Nodes(path) => path.type_check()?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,24 +1,20 @@
-use crate::{Error, Path};
+use crate::{cbor_path, Error, Path};
 use cbor_data::{Cbor, CborBuilder, CborOwned, TaggedItem, Visitor, Writer};
 use std::borrow::Cow;
 
-pub struct WriteVisitor<'a, F>
-where
-    F: FnMut(&'a Cbor) -> Result<Option<Cow<'a, Cbor>>, Error>,
-{
+type MapFunction<'a, 'f> = dyn FnMut(&'a Cbor) -> Result<Option<Cow<'a, Cbor>>, Error> + 'f;
+
+pub struct WriteVisitor<'a, 'f> {
     paths: Vec<Path>,
-    map_function: F,
+    map_function: &'f mut MapFunction<'a, 'f>,
     pending_items: Vec<Vec<Cow<'a, Cbor>>>,
     current_path: Path,
     skip_end: bool,
     is_key: bool,
 }
 
-impl<'a, F> WriteVisitor<'a, F>
-where
-    F: FnMut(&'a Cbor) -> Result<Option<Cow<'a, Cbor>>, Error>,
-{
-    pub fn new(paths: Vec<Path>, map_function: F) -> Self {
+impl<'a, 'f> WriteVisitor<'a, 'f> {
+    pub fn new(paths: Vec<Path>, map_function: &'f mut MapFunction<'a, 'f>) -> Self {
         Self {
             paths,
             map_function,
@@ -30,39 +26,68 @@ where
     }
 
     pub fn get_cbor(&mut self) -> CborOwned {
-        match self.pending_items.pop() {
-            Some(mut v) => match v.pop().map(|c| c.into_owned()) {
-                Some(v) => v,
-                None => unreachable!(),
-            },
+        match self.take_cbor() {
+            Some(v) => v,
             None => unreachable!(),
         }
     }
+
+    /// Returns [`None`] if the root item has been deleted
+    fn take_cbor(&mut self) -> Option<CborOwned> {
+        self.pending_items
+            .pop()
+            .and_then(|mut v| v.pop())
+            .map(|c| c.into_owned())
+    }
+
+    /// Applies the write operation inside the `CBOR` item embedded in the byte string `item`
+    /// and re-encodes the byte string with its tags
+    fn write_embedded(&mut self, item: TaggedItem<'a>) -> Result<Option<Cow<'a, Cbor>>, Error> {
+        let Some(embedded) = cbor_path::embedded(item.cbor()) else {
+            return Ok(Some(Cow::Borrowed(item.cbor())));
+        };
+
+        let embedded_path = self.current_path.child_from_embedded();
+        let paths = self
+            .paths
+            .iter()
+            .filter(|p| p.starts_with(&embedded_path))
+            .map(|p| p[embedded_path.len()..].iter().cloned().collect())
+            .collect();
+
+        let mut visitor = WriteVisitor::new(paths, &mut *self.map_function);
+        embedded.visit(&mut visitor)?;
+        Ok(visitor
+            .take_cbor()
+            .map(|cbor| Cow::Owned(CborBuilder::new().write_bytes(cbor.as_slice(), item.tags()))))
+    }
 }
 
-impl<'a, F> Visitor<'a, Error> for WriteVisitor<'a, F>
-where
-    F: FnMut(&'a Cbor) -> Result<Option<Cow<'a, Cbor>>, Error>,
-{
+impl<'a, 'f> Visitor<'a, Error> for WriteVisitor<'a, 'f> {
     fn visit_simple(&mut self, item: TaggedItem<'a>) -> Result<(), Error> {
+        log::trace!(
+            "[visit_simple] current_path:{}, item:{}",
+            self.current_path,
+            item.cbor()
+        );
+        let new_value = if self.paths.contains(&self.current_path) {
+            (self.map_function)(item.cbor())?
+        } else if self.paths.iter().any(|p| self.current_path.is_parent(p)) {
+            // only byte strings with embedded CBOR can be parents of a path
+            self.write_embedded(item)?
+        } else {
+            Some(Cow::Borrowed(item.cbor()))
+        };
+
         if let Some(pending_items) = self.pending_items.last_mut() {
-            log::trace!(
-                "[visit_simple] current_path:{}, item:{}",
-                self.current_path,
-                item.cbor()
-            );
-            if self.paths.iter().any(|p| self.current_path == *p) {
-                match (self.map_function)(item.cbor())? {
-                    Some(new_value) => pending_items.push(new_value),
-                    None => {
-                        if self.is_key {
-                            // remove the key that was just added
-                            pending_items.pop();
-                        }
+            match new_value {
+                Some(new_value) => pending_items.push(new_value),
+                None => {
+                    if self.is_key {
+                        // remove the key that was just added
+                        pending_items.pop();
                     }
                 }
-            } else {
-                pending_items.push(Cow::Borrowed(item.cbor()));
             }
         }
         self.is_key = false;
@@ -84,7 +109,7 @@ where
             Ok(true)
         } else {
             if let Some(pending_items) = self.pending_items.last_mut() {
-                let item = if self.paths.iter().any(|p| self.current_path == *p) {
+                let item = if self.paths.contains(&self.current_path) {
                     (self.map_function)(array.cbor())?
                 } else {
                     Some(Cow::Borrowed(array.cbor()))
@@ -109,7 +134,7 @@ where
         Ok(true)
     }
 
-    fn visit_array_end(&mut self, _array: TaggedItem<'a>) -> Result<(), Error> {
+    fn visit_array_end(&mut self, array: TaggedItem<'a>) -> Result<(), Error> {
         if self.skip_end {
             self.skip_end = false;
             return Ok(());
@@ -117,7 +142,8 @@ where
 
         if let Some(pending_items) = self.pending_items.pop() {
             self.current_path.pop();
-            let item = CborBuilder::new().write_array(None, |builder| {
+            // keep the tags of the rebuilt array
+            let item = CborBuilder::new().write_array(array.tags(), |builder| {
                 for item in pending_items.into_iter() {
                     builder.write_item(item.as_ref());
                 }
@@ -139,7 +165,7 @@ where
             self.pending_items.push(items);
             Ok(true)
         } else {
-            let item = if self.paths.iter().any(|p| self.current_path == *p) {
+            let item = if self.paths.contains(&self.current_path) {
                 (self.map_function)(dict.cbor())?
             } else {
                 Some(Cow::Borrowed(dict.cbor()))
@@ -181,7 +207,7 @@ where
         Ok(true)
     }
 
-    fn visit_dict_end(&mut self, _dict: TaggedItem<'a>) -> Result<(), Error> {
+    fn visit_dict_end(&mut self, dict: TaggedItem<'a>) -> Result<(), Error> {
         if self.skip_end {
             self.skip_end = false;
             return Ok(());
@@ -189,7 +215,8 @@ where
 
         if let Some(pending_items) = self.pending_items.pop() {
             self.current_path.pop();
-            let item = CborBuilder::new().write_dict(None, |builder| {
+            // keep the tags of the rebuilt map
+            let item = CborBuilder::new().write_dict(dict.tags(), |builder| {
                 let mut iter = pending_items.into_iter();
                 while let Some(key) = iter.next() {
                     if let Some(value) = iter.next() {